@@ -0,0 +1,740 @@
+//! Reduced ordered binary decision diagrams.
+//!
+//! A [`BddManager`] owns every node and hash-conses them through a unique
+//! table, so two [`Bdd`] handles of the same manager are equal exactly when
+//! they denote the same function. Operations are memoised in a computed
+//! cache that lives as long as the manager, or until [`BddManager::collect`].
+
+mod reorder;
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::expression::{EvaluationError, Expression};
+use crate::natural::Natural;
+use crate::satisfability::Model;
+
+pub use reorder::{Reordering, dfs_order, force_order};
+
+/// A Boolean function, as a handle to a node of a [`BddManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bdd (u32);
+
+impl Bdd {
+    pub const FALSE: Bdd = Bdd(0);
+    pub const TRUE: Bdd = Bdd(1);
+
+    pub fn constant (value: bool) -> Bdd {
+        if value { Bdd::TRUE } else { Bdd::FALSE }
+    }
+
+    pub fn is_constant (self) -> bool {
+        self.0 < 2
+    }
+
+    fn index (self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Binary operators accepted by [`BddManager::apply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    And,
+    Or,
+    Xor,
+}
+
+/// Variable of the terminal nodes.
+const TERMINAL: usize = usize::MAX;
+
+/// Variable of the slots released by [`BddManager::collect`].
+const FREE: usize = usize::MAX - 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Node {
+    variable: usize,
+    low: Bdd,
+    high: Bdd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Operation {
+    Ite,
+    Apply (Operator),
+    Not,
+    Restrict (usize, bool),
+    Exists,
+    Forall,
+}
+
+/// Creates, combines and inspects [`Bdd`]s over named variables.
+///
+/// Variables are ordered by creation, the first one being tested at the
+/// root, until [`BddManager::reorder`] or [`BddManager::sift`] moves them.
+#[derive(Debug, Clone)]
+pub struct BddManager {
+    nodes: Vec<Node>,
+    free: Vec<Bdd>,
+    unique: HashMap<Node, Bdd>,
+    cache: HashMap<(Operation, Bdd, Bdd, Bdd), Bdd>,
+
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+
+    /// Level of every variable, the root level being 0.
+    levels: Vec<usize>,
+    /// Variable at every level.
+    order: Vec<usize>,
+}
+
+impl Default for BddManager {
+    fn default () -> BddManager {
+        BddManager::new()
+    }
+}
+
+impl BddManager {
+    pub fn new () -> BddManager {
+        let terminal = Node {
+            variable: TERMINAL,
+            low: Bdd::FALSE,
+            high: Bdd::FALSE,
+        };
+
+        BddManager {
+            nodes: vec![terminal, terminal],
+            free: Vec::new(),
+            unique: HashMap::new(),
+            cache: HashMap::new(),
+            names: Vec::new(),
+            indices: HashMap::new(),
+            levels: Vec::new(),
+            order: Vec::new(),
+        }
+    }
+
+    /// Returns the variable named `name`, placing it below every existing
+    /// variable on first use.
+    pub fn variable<S: AsRef<str>> (&mut self, name: S) -> usize {
+        let name = name.as_ref();
+
+        match self.indices.get(name) {
+            Some (&variable) => variable,
+            None => {
+                let variable = self.names.len();
+
+                self.names.push(name.to_string());
+                self.indices.insert(name.to_string(), variable);
+                self.levels.push(self.order.len());
+                self.order.push(variable);
+
+                variable
+            }
+        }
+    }
+
+    pub fn variables (&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name (&self, variable: usize) -> Option<&str> {
+        self.names.get(variable).map(String::as_str)
+    }
+
+    pub fn index (&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// The function that is true when the variable named `name` is.
+    pub fn var<S: AsRef<str>> (&mut self, name: S) -> Bdd {
+        let variable = self.variable(name);
+
+        self.literal(variable, true)
+    }
+
+    /// The function that is true when `variable` takes `value`.
+    pub fn literal (&mut self, variable: usize, value: bool) -> Bdd {
+        if value {
+            self.node(variable, Bdd::FALSE, Bdd::TRUE)
+        } else {
+            self.node(variable, Bdd::TRUE, Bdd::FALSE)
+        }
+    }
+
+    /// Builds the function of `expression`, creating its variables in the
+    /// order they first appear.
+    pub fn from_expression (&mut self, expression: &Expression) -> Bdd {
+        match expression {
+            Expression::Var (name) => self.var(name),
+
+            Expression::Not (inner) => {
+                let inner = self.from_expression(inner);
+
+                self.not(inner)
+            }
+
+            Expression::And (left, right)
+            | Expression::Or (left, right)
+            | Expression::Xor (left, right)
+            | Expression::Implies (left, right)
+            | Expression::Iff (left, right) => {
+                let left = self.from_expression(left);
+                let right = self.from_expression(right);
+
+                match expression {
+                    Expression::And (..) => self.and(left, right),
+                    Expression::Or (..) => self.or(left, right),
+                    Expression::Xor (..) => self.xor(left, right),
+                    Expression::Implies (..) => self.implies(left, right),
+                    _ => self.iff(left, right),
+                }
+            }
+
+            Expression::True => Bdd::TRUE,
+            Expression::False => Bdd::FALSE,
+        }
+    }
+
+    /// `if f then g else h`.
+    pub fn ite (&mut self, f: Bdd, g: Bdd, h: Bdd) -> Bdd {
+        if f == Bdd::TRUE || g == h {
+            return g;
+        }
+
+        if f == Bdd::FALSE {
+            return h;
+        }
+
+        if g == Bdd::TRUE && h == Bdd::FALSE {
+            return f;
+        }
+
+        let key = (Operation::Ite, f, g, h);
+
+        if let Some (&result) = self.cache.get(&key) {
+            return result;
+        }
+
+        let variable = self.top(&[f, g, h]);
+
+        let (f_low, f_high) = self.cofactors(f, variable);
+        let (g_low, g_high) = self.cofactors(g, variable);
+        let (h_low, h_high) = self.cofactors(h, variable);
+
+        let low = self.ite(f_low, g_low, h_low);
+        let high = self.ite(f_high, g_high, h_high);
+
+        let result = self.node(variable, low, high);
+
+        self.cache.insert(key, result);
+
+        result
+    }
+
+    /// Combines two functions with a binary operator by Shannon expansion.
+    pub fn apply (&mut self, operator: Operator, f: Bdd, g: Bdd) -> Bdd {
+        if let Some (result) = terminal(operator, f, g) {
+            return result;
+        }
+
+        // Every operator is commutative.
+        let (f, g) = if f <= g { (f, g) } else { (g, f) };
+
+        let key = (Operation::Apply(operator), f, g, Bdd::FALSE);
+
+        if let Some (&result) = self.cache.get(&key) {
+            return result;
+        }
+
+        let variable = self.top(&[f, g]);
+
+        let (f_low, f_high) = self.cofactors(f, variable);
+        let (g_low, g_high) = self.cofactors(g, variable);
+
+        let low = self.apply(operator, f_low, g_low);
+        let high = self.apply(operator, f_high, g_high);
+
+        let result = self.node(variable, low, high);
+
+        self.cache.insert(key, result);
+
+        result
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not (&mut self, f: Bdd) -> Bdd {
+        if f.is_constant() {
+            return Bdd::constant(f == Bdd::FALSE);
+        }
+
+        let key = (Operation::Not, f, Bdd::FALSE, Bdd::FALSE);
+
+        if let Some (&result) = self.cache.get(&key) {
+            return result;
+        }
+
+        let Node { variable, low, high } = self.nodes[f.index()];
+
+        let low = self.not(low);
+        let high = self.not(high);
+
+        let result = self.node(variable, low, high);
+
+        self.cache.insert(key, result);
+
+        result
+    }
+
+    pub fn and (&mut self, f: Bdd, g: Bdd) -> Bdd {
+        self.apply(Operator::And, f, g)
+    }
+
+    pub fn or (&mut self, f: Bdd, g: Bdd) -> Bdd {
+        self.apply(Operator::Or, f, g)
+    }
+
+    pub fn xor (&mut self, f: Bdd, g: Bdd) -> Bdd {
+        self.apply(Operator::Xor, f, g)
+    }
+
+    pub fn implies (&mut self, f: Bdd, g: Bdd) -> Bdd {
+        self.ite(f, g, Bdd::TRUE)
+    }
+
+    pub fn iff (&mut self, f: Bdd, g: Bdd) -> Bdd {
+        let difference = self.xor(f, g);
+
+        self.not(difference)
+    }
+
+    /// The cofactor of `f` where `variable` is fixed to `value`.
+    pub fn restrict (&mut self, f: Bdd, variable: usize, value: bool) -> Bdd {
+        if self.level(f) > self.levels[variable] {
+            return f;
+        }
+
+        let Node { variable: top, low, high } = self.nodes[f.index()];
+
+        if top == variable {
+            return if value { high } else { low };
+        }
+
+        let key = (Operation::Restrict(variable, value), f, Bdd::FALSE, Bdd::FALSE);
+
+        if let Some (&result) = self.cache.get(&key) {
+            return result;
+        }
+
+        let low = self.restrict(low, variable, value);
+        let high = self.restrict(high, variable, value);
+
+        let result = self.node(top, low, high);
+
+        self.cache.insert(key, result);
+
+        result
+    }
+
+    /// `∃ variables. f`: true where some value of the variables makes `f` true.
+    pub fn exists (&mut self, f: Bdd, variables: &[usize]) -> Bdd {
+        let cube = self.cube(variables);
+
+        self.quantify(f, cube, true)
+    }
+
+    /// `∀ variables. f`: true where every value of the variables makes `f` true.
+    pub fn forall (&mut self, f: Bdd, variables: &[usize]) -> Bdd {
+        let cube = self.cube(variables);
+
+        self.quantify(f, cube, false)
+    }
+
+    /// Value of `f` when every variable it depends on takes its value from
+    /// `assignment`.
+    pub fn evaluate (&self, f: Bdd, assignment: &Model) -> Result<bool, EvaluationError> {
+        let mut current = f;
+
+        while !current.is_constant() {
+            let node = self.nodes[current.index()];
+            let name = &self.names[node.variable];
+
+            current = match assignment.get(name) {
+                Some (true) => node.high,
+                Some (false) => node.low,
+                None => return Err(EvaluationError::UnassignedVariable(name.clone())),
+            };
+        }
+
+        Ok(current == Bdd::TRUE)
+    }
+
+    /// An assignment of every variable of the manager making `f` true, if
+    /// any, preferring `false` for the variables `f` does not test.
+    pub fn model (&self, f: Bdd) -> Option<Model> {
+        if f == Bdd::FALSE {
+            return None;
+        }
+
+        let mut model: Model = self.names.iter().map(|name| (name.clone(), false)).collect();
+        let mut current = f;
+
+        while !current.is_constant() {
+            let node = self.nodes[current.index()];
+            let value = node.low == Bdd::FALSE;
+
+            model.insert(self.names[node.variable].clone(), value);
+
+            current = if value { node.high } else { node.low };
+        }
+
+        Some(model)
+    }
+
+    /// Number of assignments of every variable of the manager making `f` true.
+    pub fn count (&self, f: Bdd) -> Natural {
+        let mut counts = HashMap::new();
+
+        self.count_below(f, &mut counts) << self.level(f)
+    }
+
+    /// Names of the variables `f` depends on.
+    pub fn support (&self, f: Bdd) -> BTreeSet<String> {
+        self.reachable(&[f]).into_iter()
+            .filter(|bdd| !bdd.is_constant())
+            .map(|bdd| self.names[self.nodes[bdd.index()].variable].clone())
+            .collect()
+    }
+
+    /// Number of distinct nodes reachable from `roots`, terminals included.
+    pub fn size (&self, roots: &[Bdd]) -> usize {
+        self.reachable(roots).len()
+    }
+
+    /// Number of nodes currently allocated, terminals included.
+    pub fn nodes (&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    /// Releases every node that is not reachable from `roots` and empties
+    /// the computed cache. Handles to released nodes become invalid.
+    pub fn collect (&mut self, roots: &[Bdd]) {
+        let reachable = self.reachable(roots);
+
+        for index in 2..self.nodes.len() {
+            let bdd = Bdd(index as u32);
+            let node = self.nodes[index];
+
+            if node.variable != FREE && !reachable.contains(&bdd) {
+                self.unique.remove(&node);
+                self.nodes[index].variable = FREE;
+                self.free.push(bdd);
+            }
+        }
+
+        self.cache.clear();
+    }
+
+    /// The node testing `variable`, reduced and shared.
+    fn node (&mut self, variable: usize, low: Bdd, high: Bdd) -> Bdd {
+        if low == high {
+            return low;
+        }
+
+        let node = Node { variable, low, high };
+
+        if let Some (&bdd) = self.unique.get(&node) {
+            return bdd;
+        }
+
+        let bdd = match self.free.pop() {
+            Some (bdd) => {
+                self.nodes[bdd.index()] = node;
+                bdd
+            }
+
+            None => {
+                self.nodes.push(node);
+                Bdd(self.nodes.len() as u32 - 1)
+            }
+        };
+
+        self.unique.insert(node, bdd);
+
+        bdd
+    }
+
+    /// Level of the variable tested at the root of `f`, or the number of
+    /// variables for the terminals.
+    fn level (&self, f: Bdd) -> usize {
+        match self.nodes[f.index()].variable {
+            TERMINAL => self.order.len(),
+            variable => self.levels[variable],
+        }
+    }
+
+    /// The variable at the lowest level among the roots of `functions`.
+    fn top (&self, functions: &[Bdd]) -> usize {
+        let level = functions.iter().map(|&f| self.level(f)).min().unwrap();
+
+        self.order[level]
+    }
+
+    fn cofactors (&self, f: Bdd, variable: usize) -> (Bdd, Bdd) {
+        let node = self.nodes[f.index()];
+
+        if node.variable == variable {
+            (node.low, node.high)
+        } else {
+            (f, f)
+        }
+    }
+
+    /// The conjunction of the positive literals of `variables`.
+    fn cube (&mut self, variables: &[usize]) -> Bdd {
+        let mut variables = variables.to_vec();
+
+        variables.sort_by_key(|&variable| std::cmp::Reverse(self.levels[variable]));
+        variables.dedup();
+
+        variables.into_iter().fold(Bdd::TRUE, |cube, variable| {
+            self.node(variable, Bdd::FALSE, cube)
+        })
+    }
+
+    fn quantify (&mut self, f: Bdd, mut cube: Bdd, existential: bool) -> Bdd {
+        while !cube.is_constant() && self.level(cube) < self.level(f) {
+            cube = self.nodes[cube.index()].high;
+        }
+
+        if f.is_constant() || cube.is_constant() {
+            return f;
+        }
+
+        let operation = if existential { Operation::Exists } else { Operation::Forall };
+        let key = (operation, f, cube, Bdd::FALSE);
+
+        if let Some (&result) = self.cache.get(&key) {
+            return result;
+        }
+
+        let Node { variable, low, high } = self.nodes[f.index()];
+        let quantified = self.nodes[cube.index()];
+
+        let result = if quantified.variable == variable {
+            let low = self.quantify(low, quantified.high, existential);
+            let high = self.quantify(high, quantified.high, existential);
+
+            if existential {
+                self.or(low, high)
+            } else {
+                self.and(low, high)
+            }
+        } else {
+            let low = self.quantify(low, cube, existential);
+            let high = self.quantify(high, cube, existential);
+
+            self.node(variable, low, high)
+        };
+
+        self.cache.insert(key, result);
+
+        result
+    }
+
+    /// Models of `f` over the variables from its root level down.
+    fn count_below (&self, f: Bdd, counts: &mut HashMap<Bdd, Natural>) -> Natural {
+        if f.is_constant() {
+            return if f == Bdd::TRUE { Natural::one() } else { Natural::zero() };
+        }
+
+        if let Some (count) = counts.get(&f) {
+            return count.clone();
+        }
+
+        let node = self.nodes[f.index()];
+        let level = self.level(f);
+
+        let low = self.count_below(node.low, counts) << (self.level(node.low) - level - 1);
+        let high = self.count_below(node.high, counts) << (self.level(node.high) - level - 1);
+
+        let count = low + high;
+
+        counts.insert(f, count.clone());
+
+        count
+    }
+
+    fn reachable (&self, roots: &[Bdd]) -> BTreeSet<Bdd> {
+        let mut reachable = BTreeSet::new();
+        let mut pending = roots.to_vec();
+
+        while let Some (bdd) = pending.pop() {
+            if reachable.insert(bdd) && !bdd.is_constant() {
+                let node = self.nodes[bdd.index()];
+
+                pending.push(node.low);
+                pending.push(node.high);
+            }
+        }
+
+        reachable
+    }
+}
+
+fn terminal (operator: Operator, f: Bdd, g: Bdd) -> Option<Bdd> {
+    match operator {
+        Operator::And => {
+            if f == Bdd::FALSE || g == Bdd::FALSE {
+                Some(Bdd::FALSE)
+            } else if f == Bdd::TRUE || f == g {
+                Some(g)
+            } else if g == Bdd::TRUE {
+                Some(f)
+            } else {
+                None
+            }
+        }
+
+        Operator::Or => {
+            if f == Bdd::TRUE || g == Bdd::TRUE {
+                Some(Bdd::TRUE)
+            } else if f == Bdd::FALSE || f == g {
+                Some(g)
+            } else if g == Bdd::FALSE {
+                Some(f)
+            } else {
+                None
+            }
+        }
+
+        Operator::Xor => {
+            if f == g {
+                Some(Bdd::FALSE)
+            } else if f == Bdd::FALSE {
+                Some(g)
+            } else if g == Bdd::FALSE {
+                Some(f)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::satisfability::ModelCounter;
+    use crate::testing::{Random, evaluate};
+    use crate::truth_table::TruthTable;
+
+    fn build (manager: &mut BddManager, string: &str) -> Bdd {
+        manager.from_expression(&parse(string).unwrap())
+    }
+
+    #[test]
+    fn equivalent_formulas_should_share_a_node () {
+        let mut manager = BddManager::new();
+
+        let implication = build(&mut manager, "a -> b");
+        let disjunction = build(&mut manager, "¬a | b");
+        let distributed = build(&mut manager, "a & (¬a | b)");
+        let conjunction = build(&mut manager, "b & a");
+
+        assert_eq!(implication, disjunction);
+        assert_eq!(distributed, conjunction);
+        assert_eq!(build(&mut manager, "a ^ ¬a"), Bdd::TRUE);
+        assert_eq!(build(&mut manager, "(a <-> b) ^ (a ^ b)"), Bdd::TRUE);
+        assert_ne!(implication, conjunction);
+    }
+
+    #[test]
+    fn should_restrict_and_quantify () {
+        let mut manager = BddManager::new();
+
+        let f = build(&mut manager, "(a & b) | (¬a & c)");
+        let a = manager.index("a").unwrap();
+
+        let b = manager.var("b");
+        let c = manager.var("c");
+
+        assert_eq!(manager.restrict(f, a, true), b);
+        assert_eq!(manager.restrict(f, a, false), c);
+
+        let either = manager.or(b, c);
+        let both = manager.and(b, c);
+
+        assert_eq!(manager.exists(f, &[a]), either);
+        assert_eq!(manager.forall(f, &[a]), both);
+
+        let all = [a, manager.index("b").unwrap(), manager.index("c").unwrap()];
+
+        assert_eq!(manager.exists(f, &all), Bdd::TRUE);
+        assert_eq!(manager.forall(f, &all), Bdd::FALSE);
+    }
+
+    #[test]
+    fn ite_should_agree_with_apply () {
+        let mut manager = BddManager::new();
+
+        let f = build(&mut manager, "a | c");
+        let g = build(&mut manager, "b ^ c");
+
+        let not_g = manager.not(g);
+
+        assert_eq!(manager.ite(f, g, Bdd::FALSE), manager.and(f, g));
+        assert_eq!(manager.ite(f, Bdd::TRUE, g), manager.or(f, g));
+        assert_eq!(manager.ite(f, not_g, g), manager.xor(f, g));
+    }
+
+    #[test]
+    fn should_agree_with_truth_tables_and_counter () {
+        let names = ["a", "b", "c", "d", "e"];
+        let mut random = Random::new(0x6A09E667F3BCC908);
+
+        for _ in 0..100 {
+            let expression = random.expression(&names, 6);
+
+            let mut manager = BddManager::new();
+
+            for name in expression.variables() {
+                manager.variable(name);
+            }
+
+            let f = manager.from_expression(&expression);
+
+            for (values, value) in TruthTable::new(&expression).unwrap().rows() {
+                let model: Model = expression.variables().into_iter().zip(values.iter().copied()).collect();
+
+                assert_eq!(manager.evaluate(f, &model), Ok(value), "{:?} {:?}", expression, model);
+            }
+
+            assert_eq!(manager.count(f), ModelCounter::new(&expression).count(), "{:?}", expression);
+
+            match manager.model(f) {
+                Some (model) => assert!(evaluate(&expression, &model), "{:?}", expression),
+                None => assert_eq!(f, Bdd::FALSE),
+            }
+        }
+    }
+
+    #[test]
+    fn should_collect_unreachable_nodes () {
+        let mut manager = BddManager::new();
+
+        let kept = build(&mut manager, "a & b");
+        let dropped = build(&mut manager, "(c ^ d) | (e & a)");
+
+        assert!(manager.size(&[dropped]) > 1);
+
+        let before = manager.nodes();
+
+        manager.collect(&[kept]);
+
+        assert_eq!(manager.nodes(), manager.size(&[kept]));
+        assert!(manager.nodes() < before);
+        assert_eq!(build(&mut manager, "b & a"), kept);
+        assert_eq!(manager.support(kept), BTreeSet::from(["a".to_string(), "b".to_string()]));
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::BTreeSet;
+
+use crate::cnf::Cnf;
+use crate::expression::Expression;
+
+use super::{Bdd, BddManager, Node, FREE, TERMINAL};
+
+/// Sifting gives up on a direction once the diagram grows past this
+/// factor of its size when the variable started moving.
+const MAX_GROWTH: usize = 2;
+
+/// Rounds of FORCE, which usually settles well before.
+const FORCE_ITERATIONS: usize = 32;
+
+/// Node counts around a reordering, terminals included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reordering {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl BddManager {
+    /// Creates a manager whose variables are ordered as `names`.
+    pub fn with_order<I, S> (names: I) -> BddManager
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut manager = BddManager::new();
+
+        for name in names {
+            manager.variable(name);
+        }
+
+        manager
+    }
+
+    /// Names of the variables from the root level down.
+    pub fn order (&self) -> Vec<&str> {
+        self.order.iter().map(|&variable| self.names[variable].as_str()).collect()
+    }
+
+    /// Moves the variables named in `names` to the top levels in that
+    /// order, keeping the relative order of the others below them.
+    ///
+    /// Handles stay valid and keep denoting the same functions.
+    pub fn reorder<S: AsRef<str>> (&mut self, names: &[S]) {
+        let mut target = 0;
+
+        for name in names {
+            let Some (variable) = self.index(name.as_ref()) else {
+                continue;
+            };
+
+            if self.levels[variable] < target {
+                continue;
+            }
+
+            while self.levels[variable] > target {
+                self.swap(self.levels[variable] - 1);
+            }
+
+            target += 1;
+        }
+
+        self.cache.clear();
+    }
+
+    /// Rudell's sifting: every variable in turn, busiest first, is moved
+    /// through every level and left where the diagrams of `roots` are
+    /// smallest. Nodes unreachable from `roots` are collected.
+    pub fn sift (&mut self, roots: &[Bdd]) -> Reordering {
+        self.collect(roots);
+
+        let before = self.size(roots);
+
+        let mut occurrences = vec![0; self.variables()];
+
+        for node in self.nodes.iter() {
+            if node.variable != TERMINAL && node.variable != FREE {
+                occurrences[node.variable] += 1;
+            }
+        }
+
+        let mut variables: Vec<usize> = (0..self.variables()).collect();
+
+        variables.sort_by_key(|&variable| std::cmp::Reverse(occurrences[variable]));
+
+        let mut size = before;
+
+        for variable in variables {
+            size = self.sift_variable(variable, roots, size);
+
+            self.collect(roots);
+        }
+
+        Reordering {
+            before,
+            after: size,
+        }
+    }
+
+    fn sift_variable (&mut self, variable: usize, roots: &[Bdd], size: usize) -> usize {
+        let start = self.levels[variable];
+        let limit = size * MAX_GROWTH;
+        let mut best = (size, start);
+
+        while self.levels[variable] + 1 < self.order.len() {
+            self.swap(self.levels[variable]);
+
+            let size = self.size(roots);
+
+            best = best.min((size, self.levels[variable]));
+
+            if size > limit {
+                break;
+            }
+        }
+
+        while self.levels[variable] > 0 {
+            self.swap(self.levels[variable] - 1);
+
+            let size = self.size(roots);
+
+            best = best.min((size, self.levels[variable]));
+
+            if self.levels[variable] < start && size > limit {
+                break;
+            }
+        }
+
+        while self.levels[variable] < best.1 {
+            self.swap(self.levels[variable]);
+        }
+
+        while self.levels[variable] > best.1 {
+            self.swap(self.levels[variable] - 1);
+        }
+
+        best.0
+    }
+
+    /// Exchanges the variables at `level` and `level + 1`, rewriting in
+    /// place the nodes of the upper one that test the lower one.
+    fn swap (&mut self, level: usize) {
+        let upper = self.order[level];
+        let lower = self.order[level + 1];
+
+        let rewritten: Vec<usize> = (2..self.nodes.len())
+            .filter(|&index| {
+                let node = self.nodes[index];
+
+                node.variable == upper
+                    && (self.nodes[node.low.index()].variable == lower || self.nodes[node.high.index()].variable == lower)
+            })
+            .collect();
+
+        for index in rewritten {
+            let node = self.nodes[index];
+
+            let (low_low, low_high) = self.cofactors(node.low, lower);
+            let (high_low, high_high) = self.cofactors(node.high, lower);
+
+            let low = self.node(upper, low_low, high_low);
+            let high = self.node(upper, low_high, high_high);
+
+            let swapped = Node {
+                variable: lower,
+                low,
+                high,
+            };
+
+            self.unique.remove(&node);
+            self.unique.insert(swapped, Bdd(index as u32));
+            self.nodes[index] = swapped;
+        }
+
+        self.order.swap(level, level + 1);
+        self.levels[upper] = level + 1;
+        self.levels[lower] = level;
+
+        self.cache.clear();
+    }
+}
+
+/// Orders the variables as a depth-first traversal of the expression
+/// meets them, visiting the deeper operand first.
+pub fn dfs_order (expression: &Expression) -> Vec<String> {
+    fn depth (expression: &Expression) -> usize {
+        match expression {
+            Expression::Var (_) | Expression::True | Expression::False => 0,
+
+            Expression::Not (inner) => depth(inner) + 1,
+
+            Expression::And (left, right)
+            | Expression::Or (left, right)
+            | Expression::Xor (left, right)
+            | Expression::Implies (left, right)
+            | Expression::Iff (left, right) => depth(left).max(depth(right)) + 1,
+        }
+    }
+
+    fn visit (expression: &Expression, seen: &mut BTreeSet<String>, order: &mut Vec<String>) {
+        match expression {
+            Expression::Var (name) => {
+                if seen.insert(name.clone()) {
+                    order.push(name.clone());
+                }
+            }
+
+            Expression::Not (inner) => visit(inner, seen, order),
+
+            Expression::And (left, right)
+            | Expression::Or (left, right)
+            | Expression::Xor (left, right)
+            | Expression::Implies (left, right)
+            | Expression::Iff (left, right) => {
+                let (first, second) = if depth(right) > depth(left) { (right, left) } else { (left, right) };
+
+                visit(first, seen, order);
+                visit(second, seen, order);
+            }
+
+            Expression::True | Expression::False => {}
+        }
+    }
+
+    let mut order = Vec::new();
+
+    visit(expression, &mut BTreeSet::new(), &mut order);
+
+    order
+}
+
+/// Orders the variables with FORCE (Aloul, Markov and Sakallah) over the
+/// clauses of the Tseitin encoding: every variable moves to the mean of
+/// the centres of the clauses it occurs in until the total clause span
+/// stops shrinking.
+pub fn force_order (expression: &Expression) -> Vec<String> {
+    let cnf = Cnf::from_expression(expression);
+
+    let edges: Vec<Vec<usize>> = cnf.clauses().iter()
+        .map(|clause| {
+            let mut variables: Vec<usize> = clause.iter().map(|literal| literal.variable()).collect();
+
+            variables.sort();
+            variables.dedup();
+
+            variables
+        })
+        .collect();
+
+    let span = |positions: &[usize]| -> usize {
+        edges.iter()
+            .map(|edge| {
+                let positions = edge.iter().map(|&variable| positions[variable]);
+
+                positions.clone().max().unwrap_or(0) - positions.min().unwrap_or(0)
+            })
+            .sum()
+    };
+
+    let mut positions: Vec<usize> = (0..cnf.variables()).collect();
+    let mut best = span(&positions);
+
+    for _ in 0..FORCE_ITERATIONS {
+        let mut sums = vec![0.0; cnf.variables()];
+        let mut degrees = vec![0usize; cnf.variables()];
+
+        for edge in edges.iter() {
+            let centre = edge.iter().map(|&variable| positions[variable] as f64).sum::<f64>() / edge.len() as f64;
+
+            for &variable in edge {
+                sums[variable] += centre;
+                degrees[variable] += 1;
+            }
+        }
+
+        let targets: Vec<f64> = (0..cnf.variables())
+            .map(|variable| match degrees[variable] {
+                0 => positions[variable] as f64,
+                degree => sums[variable] / degree as f64,
+            })
+            .collect();
+
+        let mut ranked: Vec<usize> = (0..cnf.variables()).collect();
+
+        ranked.sort_by(|&left, &right| {
+            targets[left].total_cmp(&targets[right]).then(positions[left].cmp(&positions[right]))
+        });
+
+        let mut next = vec![0; cnf.variables()];
+
+        for (position, variable) in ranked.into_iter().enumerate() {
+            next[variable] = position;
+        }
+
+        let spanned = span(&next);
+
+        if spanned >= best {
+            break;
+        }
+
+        best = spanned;
+        positions = next;
+    }
+
+    let mut names: Vec<(usize, &str)> = cnf.names()
+        .map(|(variable, name)| (positions[variable], name))
+        .collect();
+
+    names.sort();
+
+    names.into_iter().map(|(_, name)| name.to_string()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::satisfability::Model;
+    use crate::testing::Random;
+    use crate::truth_table::TruthTable;
+
+    /// `(x0 & y0) | (x1 & y1) | ...`, exponential with every `x` above every `y`.
+    fn pairs (count: usize) -> Expression {
+        let terms: Vec<String> = (0..count).map(|index| format!("(x{} & y{})", index, index)).collect();
+
+        parse(&terms.join(" | ")).unwrap()
+    }
+
+    fn separated (count: usize) -> Vec<String> {
+        (0..count).map(|index| format!("x{}", index))
+            .chain((0..count).map(|index| format!("y{}", index)))
+            .collect()
+    }
+
+    #[test]
+    fn swapping_should_preserve_functions () {
+        let names = ["a", "b", "c", "d", "e"];
+        let mut random = Random::new(0xBB67AE8584CAA73B);
+
+        for _ in 0..50 {
+            let expression = random.expression(&names, 6);
+
+            let mut manager = BddManager::with_order(names);
+
+            let f = manager.from_expression(&expression);
+
+            for _ in 0..5 {
+                manager.swap(random.below(names.len() - 1));
+            }
+
+            for (values, value) in TruthTable::new(&expression).unwrap().rows() {
+                let model: Model = expression.variables().into_iter().zip(values.iter().copied()).collect();
+
+                assert_eq!(manager.evaluate(f, &model), Ok(value), "{:?} {:?}", expression, manager.order());
+            }
+
+            assert_eq!(manager.from_expression(&expression), f, "{:?} {:?}", expression, manager.order());
+        }
+    }
+
+    #[test]
+    fn sifting_should_shrink_separated_pairs () {
+        let expression = pairs(4);
+
+        let mut manager = BddManager::with_order(separated(4));
+
+        let f = manager.from_expression(&expression);
+
+        let reordering = manager.sift(&[f]);
+
+        assert_eq!(reordering.before, 32);
+        assert_eq!(reordering.after, 10);
+        assert_eq!(manager.size(&[f]), 10);
+        assert_eq!(manager.from_expression(&expression), f);
+    }
+
+    #[test]
+    fn should_reorder_to_a_given_order () {
+        let mut manager = BddManager::with_order(["a", "b", "c", "d"]);
+
+        let f = manager.from_expression(&parse("(a & c) | (b & d)").unwrap());
+
+        manager.reorder(&["c", "a"]);
+
+        assert_eq!(manager.order(), vec!["c", "a", "b", "d"]);
+        assert_eq!(manager.size(&[f]), 6);
+    }
+
+    #[test]
+    fn static_orders_should_keep_pairs_together () {
+        let expression = pairs(4);
+
+        for order in [dfs_order(&expression), force_order(&expression)] {
+            assert_eq!(order.iter().collect::<BTreeSet<_>>(), expression.variables().iter().collect());
+
+            let mut manager = BddManager::with_order(&order);
+
+            let f = manager.from_expression(&expression);
+
+            assert_eq!(manager.size(&[f]), 10, "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn dfs_order_should_visit_the_deeper_operand_first () {
+        assert_eq!(dfs_order(&parse("a | (b & ¬c)").unwrap()), vec!["c", "b", "a"]);
+        assert_eq!(dfs_order(&parse("(a & b) | c").unwrap()), vec!["a", "b", "c"]);
+    }
+}
@@ -1,20 +1,283 @@
-use clap::Parser;
+use clap::builder::RangedU64ValueParser;
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Tseitin,
+    PlaistedGreenbaum,
+    Direct,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Strict,
+    Symbolic,
+    Keywords,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Text,
+    Markdown,
+    Csv,
+    Latex,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Appearance,
+    Dfs,
+    Force,
+    Sift,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    Deletion,
+    QuickXplain,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Decide whether two formulas are logically equivalent, printing an
+    /// assignment telling them apart when they are not.
+    Equiv {
+        left: String,
+        right: String,
+    },
+
+    /// Print a minimal subset of the formulas that cannot all be true at
+    /// once, or `satisfiable` when they can.
+    Core {
+        #[arg(required = true)]
+        constraints: Vec<String>,
+
+        #[arg(long, value_enum, default_value_t = Extraction::QuickXplain)]
+        extraction: Extraction,
+    },
+
+    /// Print the minimal sets of formulas to drop so that the others can
+    /// all be true, smallest first, each with such an assignment.
+    Repair {
+        #[arg(required = true)]
+        constraints: Vec<String>,
+
+        /// Stop after this many sets.
+        #[arg(long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        limit: Option<usize>,
+    },
+}
 
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+#[command(group(ArgGroup::new("models").args(["all", "count"])))]
 pub struct Arguments {
-    string: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// A formula such as `(a & b) | ¬c`, or a file path with `--dimacs` or
+    /// `--wcnf`.
+    #[arg(required = true)]
+    string: Option<String>,
+
+    /// Read the input as the path of a DIMACS CNF file.
+    #[arg(long)]
+    dimacs: bool,
+
+    /// Read the input as the path of a DIMACS WCNF file and find an
+    /// assignment of least falsified soft weight.
+    #[arg(long, conflicts_with = "dimacs")]
+    wcnf: bool,
+
+    /// Print the formula as a DIMACS CNF file instead of solving it.
+    #[arg(long, conflicts_with_all = ["dimacs", "wcnf"])]
+    export_dimacs: bool,
+
+    /// How the formula is turned into clauses by `--export-dimacs`.
+    #[arg(long, value_enum, default_value_t = Encoding::Tseitin, requires = "export_dimacs")]
+    encoding: Encoding,
+
+    /// Which operator spellings the formula may use; `keywords` reserves
+    /// `and`, `or`, `not` and `xor`.
+    #[arg(long, value_enum, default_value_t = Dialect::Symbolic, global = true)]
+    dialect: Dialect,
+
+    /// Print the truth table of the formula instead of solving it.
+    #[arg(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "text", conflicts_with_all = ["dimacs", "wcnf", "export_dimacs"])]
+    truth_table: Option<TableFormat>,
+
+    /// Refuse to print truth tables of formulas with more variables, at
+    /// most 24: 2^24 rows.
+    #[arg(long, default_value_t = 16, value_parser = RangedU64ValueParser::<usize>::new().range(0..=24), requires = "truth_table")]
+    max_variables: usize,
+
+    /// Print every satisfying assignment instead of solving once.
+    #[arg(long, conflicts_with_all = ["dimacs", "wcnf", "export_dimacs", "truth_table"])]
+    all: bool,
+
+    /// Stop `--all` after this many assignments.
+    #[arg(long, value_parser = RangedU64ValueParser::<usize>::new().range(1..), requires = "all")]
+    limit: Option<usize>,
+
+    /// Only report or count these comma separated variables with `--all`
+    /// or `--count`.
+    #[arg(long, value_delimiter = ',', requires = "models")]
+    project: Option<Vec<String>>,
+
+    /// Merge the assignments of `--all` into cubes, where `-` stands for
+    /// either value.
+    #[arg(long, requires = "all")]
+    cubes: bool,
+
+    /// Print the number of satisfying assignments instead of solving.
+    #[arg(long, conflicts_with_all = ["dimacs", "wcnf", "export_dimacs", "truth_table", "all"])]
+    count: bool,
+
+    /// Build the BDD of the formula and print its node count before and
+    /// after reordering its variables.
+    #[arg(long, conflicts_with_all = ["dimacs", "wcnf", "export_dimacs", "truth_table", "all", "count"])]
+    bdd: bool,
+
+    /// How `--bdd` reorders the variables, starting from the order in
+    /// which they appear.
+    #[arg(long, value_enum, default_value_t = Order::Sift, requires = "bdd")]
+    order: Order,
+
+    /// Print a minimum sum of products equivalent to the formula.
+    #[arg(long, conflicts_with_all = ["dimacs", "wcnf", "export_dimacs", "truth_table", "all", "count", "bdd"])]
+    minimize: bool,
+
+    /// Print a small sum of products equivalent to the formula, found
+    /// heuristically, with its number of literals before and after.
+    #[arg(long, conflicts_with_all = ["dimacs", "wcnf", "export_dimacs", "truth_table", "all", "count", "bdd", "minimize"])]
+    espresso: bool,
+
+    /// A formula where `--espresso` may give either value.
+    #[arg(long, requires = "espresso")]
+    dont_care: Option<String>,
+
+    /// Also print the requirements for the formula to be true, which may
+    /// take exponential time.
+    #[arg(long, conflicts_with_all = ["dimacs", "wcnf", "export_dimacs", "truth_table", "all", "count", "bdd", "minimize", "espresso"])]
+    requirements: bool,
 }
 
 impl Arguments {
+    pub fn command (&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+
     pub fn string (&self) -> &str {
-        &self.string
+        self.string.as_deref().expect("formula should be required without a subcommand")
+    }
+
+    pub fn dimacs (&self) -> bool {
+        self.dimacs
+    }
+
+    pub fn wcnf (&self) -> bool {
+        self.wcnf
+    }
+
+    pub fn export_dimacs (&self) -> bool {
+        self.export_dimacs
+    }
+
+    pub fn encoding (&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn dialect (&self) -> Dialect {
+        self.dialect
+    }
+
+    pub fn truth_table (&self) -> Option<TableFormat> {
+        self.truth_table
+    }
+
+    pub fn max_variables (&self) -> usize {
+        self.max_variables
+    }
+
+    pub fn all (&self) -> bool {
+        self.all
+    }
+
+    pub fn limit (&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub fn project (&self) -> Option<&[String]> {
+        self.project.as_deref()
+    }
+
+    pub fn cubes (&self) -> bool {
+        self.cubes
+    }
+
+    pub fn count (&self) -> bool {
+        self.count
+    }
+
+    pub fn bdd (&self) -> bool {
+        self.bdd
+    }
+
+    pub fn order (&self) -> Order {
+        self.order
+    }
+
+    pub fn minimize (&self) -> bool {
+        self.minimize
+    }
+
+    pub fn espresso (&self) -> bool {
+        self.espresso
+    }
+
+    pub fn dont_care (&self) -> Option<&str> {
+        self.dont_care.as_deref()
+    }
+
+    pub fn requirements (&self) -> bool {
+        self.requirements
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub struct CLI;
 
 impl CLI {
     pub fn arguments () -> Arguments {
         Arguments::parse()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use clap::error::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn should_reject_a_zero_limit () {
+        let error = Arguments::try_parse_from(["satisfability-problem", "--all", "--limit", "0", "a | b"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+
+        let arguments = Arguments::try_parse_from(["satisfability-problem", "--all", "--limit", "1", "a | b"]).unwrap();
+
+        assert_eq!(arguments.limit(), Some(1));
+    }
+
+    #[test]
+    fn should_reject_a_zero_repair_limit () {
+        let error = Arguments::try_parse_from(["satisfability-problem", "repair", "--limit", "0", "a", "¬a"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+
+        let arguments = Arguments::try_parse_from(["satisfability-problem", "repair", "--limit", "1", "a", "¬a"]).unwrap();
+
+        assert!(matches!(arguments.command(), Some (Command::Repair { limit: Some (1), .. })));
+    }
+}
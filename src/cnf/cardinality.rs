@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::iter;
+
+use super::{Cnf, Literal};
+
+/// Adds clauses forcing at most `bound` of `literals` to be true, with
+/// Sinz's sequential counter: the auxiliary variable `counters[i][j]` is
+/// true when at least `j + 1` of the first `i + 1` literals are.
+pub fn at_most (cnf: &mut Cnf, literals: &[Literal], bound: usize) {
+    if bound >= literals.len() {
+        return;
+    }
+
+    if bound == 0 {
+        for &literal in literals {
+            cnf.add_clause(vec![!literal]);
+        }
+
+        return;
+    }
+
+    let counters: Vec<Vec<Literal>> = literals[..literals.len() - 1].iter()
+        .map(|_| (0..bound).map(|_| Literal::positive(cnf.fresh())).collect())
+        .collect();
+
+    for (index, &literal) in literals.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| &counters[index]);
+
+        if let Some (previous) = previous {
+            // Overflow: the literal cannot be true once the bound is reached.
+            cnf.add_clause(vec![!literal, !previous[bound - 1]]);
+        }
+
+        let Some (current) = counters.get(index) else {
+            break;
+        };
+
+        cnf.add_clause(vec![!literal, current[0]]);
+
+        match previous {
+            Some (previous) => {
+                for count in 0..bound {
+                    cnf.add_clause(vec![!previous[count], current[count]]);
+                }
+
+                for count in 1..bound {
+                    cnf.add_clause(vec![!literal, !previous[count - 1], current[count]]);
+                }
+            }
+
+            None => {
+                for &counter in current[1..].iter() {
+                    cnf.add_clause(vec![!counter]);
+                }
+            }
+        }
+    }
+}
+
+/// Largest number of clauses [`Cnf::add_weight_counter`] may add.
+pub const WEIGHT_COUNTER_LIMIT: u64 = 1 << 22;
+
+/// Generalized totalizer over weighted literals: returns a literal for
+/// every sum of weights up to `cap` that some literals reach, implied true
+/// when the true literals weigh at least that sum. Sums above `cap` count
+/// as `cap`, which keeps the encoding small when only lower bounds matter.
+///
+/// Weights are divided by their greatest common divisor first. Each node
+/// of the tree still has an output per reachable sum, up to `cap` divided
+/// likewise, and `(left + 1) * (right + 1)` clauses for the outputs of its
+/// children, so distinct large weights make the encoding grow with `cap`
+/// times the number of literals. Returns `None`, adding nothing, when that
+/// bound exceeds [`WEIGHT_COUNTER_LIMIT`] clauses.
+pub fn weight_counter (cnf: &mut Cnf, weighted: &[(Literal, u64)], cap: u64) -> Option<BTreeMap<u64, Literal>> {
+    let divisor = weighted.iter()
+        .fold(0, |divisor, &(_, weight)| gcd(divisor, weight))
+        .max(1);
+
+    let scaled: Vec<(Literal, u64)> = weighted.iter()
+        .map(|&(literal, weight)| (literal, weight / divisor))
+        .collect();
+
+    let scaled_cap = cap.div_ceil(divisor);
+
+    if size(&scaled, scaled_cap).1 > WEIGHT_COUNTER_LIMIT {
+        return None;
+    }
+
+    // Every reachable sum is a multiple of `divisor`, so none lies between
+    // the last one below `cap` and `cap` itself.
+    let outputs = totalizer(cnf, &scaled, scaled_cap).into_iter()
+        .map(|(sum, literal)| (sum.saturating_mul(divisor).min(cap), literal))
+        .collect();
+
+    Some(outputs)
+}
+
+/// Upper bounds on the number of outputs and clauses of [`totalizer`], with
+/// the total weight of `weighted`.
+fn size (weighted: &[(Literal, u64)], cap: u64) -> (u64, u64, u64) {
+    match weighted {
+        [] => (0, 0, 0),
+        [(_, weight)] => (1, 0, *weight),
+
+        _ => {
+            let (left, right) = weighted.split_at(weighted.len() / 2);
+
+            let (left_outputs, left_clauses, left_weight) = size(left, cap);
+            let (right_outputs, right_clauses, right_weight) = size(right, cap);
+
+            let pairs = (left_outputs + 1).saturating_mul(right_outputs + 1) - 1;
+            let weight = left_weight.saturating_add(right_weight);
+
+            let outputs = pairs.min(weight).min(cap);
+            let clauses = left_clauses.saturating_add(right_clauses).saturating_add(pairs);
+
+            (outputs, clauses, weight)
+        }
+    }
+}
+
+fn gcd (left: u64, right: u64) -> u64 {
+    if right == 0 {
+        left
+    } else {
+        gcd(right, left % right)
+    }
+}
+
+fn totalizer (cnf: &mut Cnf, weighted: &[(Literal, u64)], cap: u64) -> BTreeMap<u64, Literal> {
+    match weighted {
+        [] => BTreeMap::new(),
+        [(literal, weight)] => BTreeMap::from([((*weight).min(cap), *literal)]),
+
+        _ => {
+            let (left, right) = weighted.split_at(weighted.len() / 2);
+
+            let left = totalizer(cnf, left, cap);
+            let right = totalizer(cnf, right, cap);
+
+            // Every side may also contribute nothing, with no literal to assume.
+            let terms = |outputs: BTreeMap<u64, Literal>| -> Vec<(u64, Option<Literal>)> {
+                iter::once((0, None))
+                    .chain(outputs.into_iter().map(|(sum, literal)| (sum, Some(literal))))
+                    .collect()
+            };
+
+            let left = terms(left);
+            let right = terms(right);
+
+            let mut outputs = BTreeMap::new();
+
+            for &(left_sum, left_literal) in left.iter() {
+                for &(right_sum, right_literal) in right.iter() {
+                    let sum = left_sum.saturating_add(right_sum).min(cap);
+
+                    if sum == 0 {
+                        continue;
+                    }
+
+                    let output = *outputs.entry(sum).or_insert_with(|| Literal::positive(cnf.fresh()));
+
+                    let mut clause: Vec<Literal> = [left_literal, right_literal].into_iter()
+                        .flatten()
+                        .map(|literal| !literal)
+                        .collect();
+
+                    clause.push(output);
+
+                    cnf.add_clause(clause);
+                }
+            }
+
+            outputs
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::satisfability::CdclSolver;
+    use crate::testing::brute_force;
+
+    #[test]
+    fn should_bound_the_number_of_true_literals () {
+        for count in 1..5 {
+            for bound in 0..=count {
+                for ones in 0..=count {
+                    let mut cnf = Cnf::new();
+
+                    let literals: Vec<Literal> = (0..count).map(|_| Literal::positive(cnf.fresh())).collect();
+
+                    at_most(&mut cnf, &literals, bound);
+
+                    for (index, &literal) in literals.iter().enumerate() {
+                        cnf.add_clause(vec![if index < ones { literal } else { !literal }]);
+                    }
+
+                    assert_eq!(brute_force(cnf.clauses(), cnf.variables()), ones <= bound, "{} {} {}", count, bound, ones);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn weight_counter_should_bound_the_weight_of_true_literals () {
+        let weights = [3, 5, 2, 5];
+
+        weight_counter_should_bound(&weights, 9, 1..=9);
+
+        // Scaled down by 4 before encoding, with a cap between two sums.
+        let weights = [12, 20, 8, 20];
+
+        weight_counter_should_bound(&weights, 30, [4, 8, 12, 28, 29, 30]);
+    }
+
+    #[test]
+    fn weight_counter_should_refuse_to_grow_too_large () {
+        let mut cnf = Cnf::new();
+
+        // Every subset of these weights has a sum of its own.
+        let weighted: Vec<(Literal, u64)> = (0..40).map(|bit| (Literal::positive(cnf.fresh()), 1 << bit)).collect();
+
+        assert_eq!(weight_counter(&mut cnf, &weighted, u64::MAX), None);
+        assert_eq!((cnf.variables(), cnf.clauses().len()), (40, 0));
+
+        assert!(weight_counter(&mut cnf, &weighted[..8], u64::MAX).is_some());
+    }
+
+    fn weight_counter_should_bound (weights: &[u64], cap: u64, bounds: impl IntoIterator<Item = u64> + Clone) {
+        for bits in 0..1u32 << weights.len() {
+            let weight: u64 = (0..weights.len()).filter(|index| bits & (1 << index) != 0).map(|index| weights[index]).sum();
+
+            for bound in bounds.clone() {
+                let mut cnf = Cnf::new();
+
+                let weighted: Vec<(Literal, u64)> = weights.iter().map(|&weight| (Literal::positive(cnf.fresh()), weight)).collect();
+
+                let outputs = weight_counter(&mut cnf, &weighted, cap).unwrap();
+
+                for (&sum, &output) in outputs.iter() {
+                    if sum >= bound {
+                        cnf.add_clause(vec![!output]);
+                    }
+                }
+
+                for (index, &(literal, _)) in weighted.iter().enumerate() {
+                    cnf.add_clause(vec![if bits & (1 << index) != 0 { literal } else { !literal }]);
+                }
+
+                assert_eq!(CdclSolver::from_cnf(&cnf).solve().is_some(), weight < bound, "{} {}", bits, bound);
+            }
+        }
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{Clause, Cnf, Literal};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DimacsError {
+    MissingHeader,
+    InvalidHeader (usize),
+    InvalidWeightedHeader (usize),
+    DuplicateHeader (usize),
+    ClauseBeforeHeader (usize),
+
+    InvalidLiteral (usize, String),
+    InvalidWeight (usize, String),
+    VariableOutOfRange (usize, i64),
+    UnterminatedClause (usize),
+
+    ClauseCountMismatch (usize, usize),
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DimacsError::MissingHeader => write!(formatter, "missing `p cnf <variables> <clauses>` header"),
+            DimacsError::InvalidHeader (line) => write!(formatter, "line {}: expected `p cnf <variables> <clauses>`", line),
+            DimacsError::InvalidWeightedHeader (line) => write!(formatter, "line {}: expected `p wcnf <variables> <clauses> [<top>]`", line),
+            DimacsError::DuplicateHeader (line) => write!(formatter, "line {}: duplicate problem header", line),
+            DimacsError::ClauseBeforeHeader (line) => write!(formatter, "line {}: clause before the problem header", line),
+            DimacsError::InvalidLiteral (line, token) => write!(formatter, "line {}: `{}` is not a literal", line, token),
+            DimacsError::InvalidWeight (line, token) => write!(formatter, "line {}: `{}` is not a positive weight or `h`", line, token),
+            DimacsError::VariableOutOfRange (line, literal) => write!(formatter, "line {}: literal {} exceeds the declared variable count", line, literal),
+            DimacsError::UnterminatedClause (line) => write!(formatter, "line {}: clause is not terminated by `0`", line),
+            DimacsError::ClauseCountMismatch (expected, found) => write!(formatter, "header declares {} clauses but {} were found", expected, found),
+        }
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+/// Name given to DIMACS variable `number` (1-based).
+pub fn variable_name (number: usize) -> String {
+    format!("x{}", number)
+}
+
+/// The DIMACS number a name given by [`variable_name`] stands for.
+pub fn variable_number (name: &str) -> Option<usize> {
+    name.strip_prefix('x')?.parse().ok()
+}
+
+/// Reads a DIMACS CNF file. Variable `n` is named `xn` and gets the next
+/// index the first time a clause uses it, so variables the header declares
+/// but no clause mentions are left out.
+pub fn parse (input: &str) -> Result<Cnf, DimacsError> {
+    let mut cnf = Cnf::new();
+    let mut indices = HashMap::new();
+    let mut header: Option<(usize, usize)> = None;
+    let mut clause = Vec::new();
+    let mut clause_line = 0;
+    let mut found = 0;
+
+    for (number, line) in content_lines(input) {
+        if line.starts_with('p') {
+            if header.is_some() {
+                return Err(DimacsError::DuplicateHeader(number));
+            }
+
+            let (variables, clauses) = parse_header(line)
+                .ok_or(DimacsError::InvalidHeader(number))?;
+
+            header = Some((variables, clauses));
+
+            continue;
+        }
+
+        let (variables, _) = header.ok_or(DimacsError::ClauseBeforeHeader(number))?;
+
+        for token in line.split_whitespace() {
+            let literal: i64 = token.parse()
+                .map_err(|_| DimacsError::InvalidLiteral(number, token.to_string()))?;
+
+            if literal == 0 {
+                cnf.add_clause(std::mem::take(&mut clause));
+                found += 1;
+
+                continue;
+            }
+
+            if literal.unsigned_abs() > variables as u64 {
+                return Err(DimacsError::VariableOutOfRange(number, literal));
+            }
+
+            if clause.is_empty() {
+                clause_line = number;
+            }
+
+            let variable = variable(&mut cnf, &mut indices, literal.unsigned_abs() as usize);
+
+            clause.push(Literal::new(variable, literal > 0));
+        }
+    }
+
+    let (_, expected) = header.ok_or(DimacsError::MissingHeader)?;
+
+    if !clause.is_empty() {
+        return Err(DimacsError::UnterminatedClause(clause_line));
+    }
+
+    if found != expected {
+        return Err(DimacsError::ClauseCountMismatch(expected, found));
+    }
+
+    Ok(cnf)
+}
+
+/// A weighted partial MaxSAT instance: clauses that must hold, and soft
+/// clauses whose weights are paid when they do not.
+#[derive(Debug, Clone, Default)]
+pub struct Wcnf {
+    pub hard: Cnf,
+    pub soft: Vec<(Clause, u64)>,
+}
+
+/// Reads a DIMACS WCNF file, either with a `p wcnf <variables> <clauses>
+/// [<top>]` header where clauses weighing `top` or more are hard, or in the
+/// headerless format where hard clauses start with `h`. Variables are
+/// named and numbered as in [`parse`].
+pub fn parse_wcnf (input: &str) -> Result<Wcnf, DimacsError> {
+    let mut wcnf = Wcnf::default();
+    let mut indices = HashMap::new();
+    let mut header: Option<(usize, usize, Option<u64>)> = None;
+    let mut weight: Option<Option<u64>> = None;
+    let mut clause = Vec::new();
+    let mut clause_line = 0;
+    let mut found = 0;
+
+    for (number, line) in content_lines(input) {
+        if line.starts_with('p') {
+            if header.is_some() || found > 0 || weight.is_some() {
+                return Err(DimacsError::DuplicateHeader(number));
+            }
+
+            let parsed = parse_weighted_header(line)
+                .ok_or(DimacsError::InvalidWeightedHeader(number))?;
+
+            header = Some(parsed);
+
+            continue;
+        }
+
+        for token in line.split_whitespace() {
+            // The first token of every clause is its weight, `None` if hard.
+            let Some (current) = weight else {
+                let parsed = match (token, header) {
+                    ("h", None) => None,
+                    (_, header) => match token.parse::<u64>() {
+                        Ok (value) if value > 0 => match header {
+                            Some ((_, _, Some (top))) if value >= top => None,
+                            _ => Some(value),
+                        },
+                        _ => return Err(DimacsError::InvalidWeight(number, token.to_string())),
+                    },
+                };
+
+                weight = Some(parsed);
+                clause_line = number;
+
+                continue;
+            };
+
+            let literal: i64 = token.parse()
+                .map_err(|_| DimacsError::InvalidLiteral(number, token.to_string()))?;
+
+            if literal == 0 {
+                let clause = std::mem::take(&mut clause);
+
+                match current {
+                    Some (value) => wcnf.soft.push((clause, value)),
+                    None => wcnf.hard.add_clause(clause),
+                }
+
+                weight = None;
+                found += 1;
+
+                continue;
+            }
+
+            if let Some ((variables, _, _)) = header {
+                if literal.unsigned_abs() > variables as u64 {
+                    return Err(DimacsError::VariableOutOfRange(number, literal));
+                }
+            }
+
+            let variable = variable(&mut wcnf.hard, &mut indices, literal.unsigned_abs() as usize);
+
+            clause.push(Literal::new(variable, literal > 0));
+        }
+    }
+
+    if weight.is_some() {
+        return Err(DimacsError::UnterminatedClause(clause_line));
+    }
+
+    if let Some ((_, expected, _)) = header {
+        if found != expected {
+            return Err(DimacsError::ClauseCountMismatch(expected, found));
+        }
+    }
+
+    Ok(wcnf)
+}
+
+/// The trimmed lines of `input` that are neither blank nor comments, with
+/// their 1-based numbers, up to the `%` line SATLIB benchmarks end with
+/// before some garbage.
+fn content_lines (input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .take_while(|(_, line)| !line.starts_with('%'))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('c'))
+}
+
+/// Returns the index of DIMACS variable `number`, creating it on first use.
+fn variable (cnf: &mut Cnf, indices: &mut HashMap<usize, usize>, number: usize) -> usize {
+    *indices.entry(number)
+        .or_insert_with(|| cnf.variable(variable_name(number)))
+}
+
+/// Writes a DIMACS CNF file. Variable index `i` becomes number `i + 1`, and
+/// every named variable is listed in a `c <number> <name>` comment line.
+pub fn write (cnf: &Cnf) -> String {
+    let mut output = String::new();
+
+    for (variable, name) in cnf.names() {
+        output.push_str(&format!("c {} {}\n", variable + 1, name));
+    }
+
+    output.push_str(&format!("p cnf {} {}\n", cnf.variables(), cnf.clauses().len()));
+
+    for clause in cnf.clauses() {
+        for literal in clause {
+            output.push_str(&format!("{} ", literal_number(*literal)));
+        }
+
+        output.push_str("0\n");
+    }
+
+    output
+}
+
+/// The signed DIMACS number of a literal.
+pub fn literal_number (literal: Literal) -> i64 {
+    let number = literal.variable() as i64 + 1;
+
+    if literal.is_positive() {
+        number
+    } else {
+        -number
+    }
+}
+
+fn parse_header (line: &str) -> Option<(usize, usize)> {
+    let mut fields = line.split_whitespace();
+
+    match (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) {
+        (Some ("p"), Some ("cnf"), Some (variables), Some (clauses), None) => {
+            Some((variables.parse().ok()?, clauses.parse().ok()?))
+        }
+
+        _ => None,
+    }
+}
+
+fn parse_weighted_header (line: &str) -> Option<(usize, usize, Option<u64>)> {
+    let mut fields = line.split_whitespace();
+
+    match (fields.next(), fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) {
+        (Some ("p"), Some ("wcnf"), Some (variables), Some (clauses), top, None) => {
+            let top = match top {
+                Some (top) => Some(top.parse().ok()?),
+                None => None,
+            };
+
+            Some((variables.parse().ok()?, clauses.parse().ok()?, top))
+        }
+
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_parse_comments_and_multi_line_clauses () {
+        let cnf = parse("c example\np cnf 3 2\n1 -3\n 0 2 3\n-1 0\n").unwrap();
+
+        assert_eq!(cnf.variables(), 3);
+        assert_eq!(cnf.name(1), Some("x3"));
+        assert_eq!(
+            cnf.clauses(),
+            &[
+                vec![Literal::positive(0), Literal::negative(1)],
+                vec![Literal::positive(2), Literal::positive(1), Literal::negative(0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn should_write_name_map_and_clauses () {
+        let mut cnf = Cnf::new();
+
+        let a = cnf.variable("a");
+        let auxiliary = cnf.fresh();
+        let b = cnf.variable("b");
+
+        cnf.add_clause(vec![Literal::positive(a), Literal::negative(auxiliary)]);
+        cnf.add_clause(vec![Literal::negative(b)]);
+
+        let output = write(&cnf);
+
+        assert_eq!(output, "c 1 a\nc 3 b\np cnf 3 2\n1 -2 0\n-3 0\n");
+        assert_eq!(parse(&output).unwrap().clauses(), cnf.clauses());
+    }
+
+    #[test]
+    fn should_stop_at_percent_line () {
+        let cnf = parse("p cnf 1 1\n1 0\n%\n0\n").unwrap();
+
+        assert_eq!(cnf.clauses().len(), 1);
+    }
+
+    #[test]
+    fn should_reject_malformed_headers () {
+        assert_eq!(parse("1 0\n").unwrap_err(), DimacsError::ClauseBeforeHeader(1));
+        assert_eq!(parse("c only comments\n").unwrap_err(), DimacsError::MissingHeader);
+        assert_eq!(parse("p cnf x 1\n").unwrap_err(), DimacsError::InvalidHeader(1));
+        assert_eq!(parse("p dnf 1 1\n").unwrap_err(), DimacsError::InvalidHeader(1));
+        assert_eq!(parse("p cnf 1 1\np cnf 1 1\n").unwrap_err(), DimacsError::DuplicateHeader(2));
+    }
+
+    #[test]
+    fn should_reject_malformed_clauses () {
+        assert_eq!(parse("p cnf 2 1\n1 a 0\n").unwrap_err(), DimacsError::InvalidLiteral(2, "a".to_string()));
+        assert_eq!(parse("p cnf 2 1\n1 -3 0\n").unwrap_err(), DimacsError::VariableOutOfRange(2, -3));
+        assert_eq!(parse("p cnf 2 1\n1 0\n\n2\n").unwrap_err(), DimacsError::UnterminatedClause(4));
+    }
+
+    #[test]
+    fn should_parse_weighted_clauses_with_top () {
+        let wcnf = parse_wcnf("c weighted\np wcnf 2 3 10\n10 1 2 0\n3 -1\n0\n10 -2 0\n").unwrap();
+
+        assert_eq!(wcnf.hard.variables(), 2);
+        assert_eq!(
+            wcnf.hard.clauses(),
+            &[vec![Literal::positive(0), Literal::positive(1)], vec![Literal::negative(1)]],
+        );
+        assert_eq!(wcnf.soft, vec![(vec![Literal::negative(0)], 3)]);
+    }
+
+    #[test]
+    fn should_parse_headerless_weighted_clauses () {
+        let wcnf = parse_wcnf("h 1 -3 0\n5 3 0\n1 2 0\n").unwrap();
+
+        assert_eq!(wcnf.hard.variables(), 3);
+        assert_eq!(wcnf.hard.name(1), Some("x3"));
+        assert_eq!(wcnf.hard.clauses(), &[vec![Literal::positive(0), Literal::negative(1)]]);
+        assert_eq!(wcnf.soft, vec![(vec![Literal::positive(1)], 5), (vec![Literal::positive(2)], 1)]);
+    }
+
+    #[test]
+    fn should_stop_weighted_clauses_at_percent_line () {
+        let wcnf = parse_wcnf("p wcnf 2 2 10\n10 1 0\n3 -1 0\n%\n0\n").unwrap();
+
+        assert_eq!(wcnf.hard.clauses().len(), 1);
+        assert_eq!(wcnf.soft.len(), 1);
+    }
+
+    #[test]
+    fn should_reject_malformed_weighted_clauses () {
+        assert_eq!(parse_wcnf("p wcnf 1 1 x\n").unwrap_err(), DimacsError::InvalidWeightedHeader(1));
+        assert_eq!(parse_wcnf("0 1 0\n").unwrap_err(), DimacsError::InvalidWeight(1, "0".to_string()));
+        assert_eq!(parse_wcnf("p wcnf 1 1\nh 1 0\n").unwrap_err(), DimacsError::InvalidWeight(2, "h".to_string()));
+        assert_eq!(parse_wcnf("p wcnf 1 1\n2 -2 0\n").unwrap_err(), DimacsError::VariableOutOfRange(2, -2));
+        assert_eq!(parse_wcnf("2 1\n").unwrap_err(), DimacsError::UnterminatedClause(1));
+        assert_eq!(parse_wcnf("p wcnf 1 2\n2 1 0\n").unwrap_err(), DimacsError::ClauseCountMismatch(2, 1));
+    }
+
+    #[test]
+    fn should_only_create_the_variables_clauses_use () {
+        let cnf = parse("p cnf 2000000000 2\n1 -3 0\n2 0\n").unwrap();
+
+        assert_eq!(cnf.variables(), 3);
+        assert_eq!(cnf.name(1), Some("x3"));
+
+        let wcnf = parse_wcnf("p wcnf 2000000000 1\n4 2 0\n").unwrap();
+
+        assert_eq!(wcnf.hard.variables(), 1);
+        assert_eq!(wcnf.hard.name(0), Some("x2"));
+    }
+
+    #[test]
+    fn should_parse_huge_variable_numbers () {
+        let cnf = parse("p cnf 4000000000 1\n-4000000000 0\n").unwrap();
+
+        assert_eq!(cnf.variables(), 1);
+        assert_eq!(cnf.name(0), Some("x4000000000"));
+        assert_eq!(cnf.clauses(), &[vec![Literal::negative(0)]]);
+
+        let wcnf = parse_wcnf("h 4000000000 0\n").unwrap();
+
+        assert_eq!(wcnf.hard.variables(), 1);
+        assert_eq!(variable_number(wcnf.hard.name(0).unwrap()), Some(4000000000));
+    }
+
+    #[test]
+    fn should_reject_clause_count_mismatch () {
+        assert_eq!(parse("p cnf 2 3\n1 0\n2 0\n").unwrap_err(), DimacsError::ClauseCountMismatch(3, 2));
+    }
+}
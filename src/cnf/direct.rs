@@ -0,0 +1,132 @@
+use crate::expression::Expression;
+
+use super::{Cnf, Clause, Literal};
+
+/// Clauses equivalent to `expression` (or to its negation when `positive`
+/// is `false`), obtained by pushing negations inwards and distributing
+/// `|` over `&`. No auxiliary variable is introduced, at the price of a
+/// clause count that can grow exponentially.
+pub fn clauses (cnf: &mut Cnf, expression: &Expression, positive: bool) -> Vec<Clause> {
+    match (expression, positive) {
+        (Expression::Var (name), _) => {
+            let variable = cnf.variable(name);
+
+            vec![vec![Literal::new(variable, positive)]]
+        }
+
+        (Expression::True, true) | (Expression::False, false) => Vec::new(),
+        (Expression::True, false) | (Expression::False, true) => vec![Vec::new()],
+
+        (Expression::Not (inner), _) => clauses(cnf, inner, !positive),
+
+        (Expression::And (left, right), true) | (Expression::Or (left, right), false) => {
+            let mut left = clauses(cnf, left, positive);
+
+            left.extend(clauses(cnf, right, positive));
+
+            left
+        }
+
+        (Expression::And (left, right), false) | (Expression::Or (left, right), true) => {
+            let left = clauses(cnf, left, positive);
+            let right = clauses(cnf, right, positive);
+
+            product(&left, &right)
+        }
+
+        (Expression::Xor (left, right), _) => {
+            let left_true = clauses(cnf, left, true);
+            let left_false = clauses(cnf, left, false);
+            let right_true = clauses(cnf, right, positive);
+            let right_false = clauses(cnf, right, !positive);
+
+            let mut result = product(&left_true, &right_true);
+
+            result.extend(product(&left_false, &right_false));
+
+            result
+        }
+
+        (Expression::Implies (left, right), true) => {
+            let left = clauses(cnf, left, false);
+            let right = clauses(cnf, right, true);
+
+            product(&left, &right)
+        }
+
+        (Expression::Implies (left, right), false) => {
+            let mut left = clauses(cnf, left, true);
+
+            left.extend(clauses(cnf, right, false));
+
+            left
+        }
+
+        (Expression::Iff (left, right), _) => {
+            let left_true = clauses(cnf, left, true);
+            let left_false = clauses(cnf, left, false);
+            let right_true = clauses(cnf, right, !positive);
+            let right_false = clauses(cnf, right, positive);
+
+            let mut result = product(&left_true, &right_true);
+
+            result.extend(product(&left_false, &right_false));
+
+            result
+        }
+    }
+}
+
+/// Disjunction of two conjunctions of clauses, dropping tautologies.
+fn product (left: &[Clause], right: &[Clause]) -> Vec<Clause> {
+    let mut result = Vec::new();
+
+    for left in left {
+        for right in right {
+            let mut clause: Clause = left.iter().chain(right).copied().collect();
+
+            clause.sort();
+            clause.dedup();
+
+            if clause.windows(2).all(|pair| pair[0] != !pair[1]) {
+                result.push(clause);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::satisfability::Model;
+    use crate::testing::{Random, evaluate};
+
+    #[test]
+    fn direct_conversion_should_be_equivalent () {
+        let names = ["a", "b", "c"];
+        let mut random = Random::new(0xBB67AE8584CAA73B);
+
+        for _ in 0..200 {
+            let expression = random.expression(&names, 4);
+
+            let cnf = Cnf::from_expression_direct(&expression);
+
+            assert!(cnf.names().count() == cnf.variables());
+
+            for bits in 0..1u32 << names.len() {
+                let model: Model = names.iter()
+                    .enumerate()
+                    .map(|(index, name)| (name.to_string(), bits & (1 << index) != 0))
+                    .collect();
+
+                let satisfied = cnf.clauses().iter().all(|clause| {
+                    clause.iter().any(|literal| model[cnf.name(literal.variable()).unwrap()] == literal.is_positive())
+                });
+
+                assert_eq!(satisfied, evaluate(&expression, &model), "{:?}", expression);
+            }
+        }
+    }
+}
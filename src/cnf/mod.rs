@@ -0,0 +1,240 @@
+//! Clause form of expressions, with Tseitin, Plaisted–Greenbaum and direct
+//! encoders, cardinality constraints and DIMACS input and output.
+
+mod cardinality;
+pub mod dimacs;
+mod direct;
+mod tseitin;
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Not;
+
+use crate::expression::Expression;
+use crate::satisfability::Model;
+
+pub use cardinality::WEIGHT_COUNTER_LIMIT;
+pub use tseitin::{Encoding, Polarity};
+
+/// A variable or its negation, packed as `variable * 2 + negated`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Literal (u32);
+
+impl Literal {
+    pub fn new (variable: usize, positive: bool) -> Literal {
+        Literal(((variable as u32) << 1) | (!positive as u32))
+    }
+
+    pub fn positive (variable: usize) -> Literal {
+        Literal::new(variable, true)
+    }
+
+    pub fn negative (variable: usize) -> Literal {
+        Literal::new(variable, false)
+    }
+
+    pub fn variable (self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    pub fn is_positive (self) -> bool {
+        self.0 & 1 == 0
+    }
+
+    /// Dense index of the literal, suitable for tables holding both polarities.
+    pub fn index (self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Literal {
+    type Output = Literal;
+
+    fn not (self) -> Literal {
+        Literal(self.0 ^ 1)
+    }
+}
+
+pub type Clause = Vec<Literal>;
+
+/// A clause database over numbered variables, some of which carry the
+/// name of an [`Expression::Var`].
+#[derive(Debug, Clone, Default)]
+pub struct Cnf {
+    names: Vec<Option<String>>,
+    indices: HashMap<String, usize>,
+    clauses: Vec<Clause>,
+    truth: Option<Literal>,
+}
+
+impl Cnf {
+    pub fn new () -> Cnf {
+        Cnf::default()
+    }
+
+    /// Builds a clause database that is satisfiable exactly when `expression` is.
+    pub fn from_expression (expression: &Expression) -> Cnf {
+        let mut cnf = Cnf::new();
+
+        let literal = cnf.encode(expression);
+
+        cnf.add_clause(vec![literal]);
+
+        cnf
+    }
+
+    /// Like [`Cnf::from_expression`], with a choice of encoding.
+    pub fn from_expression_with (expression: &Expression, encoding: Encoding) -> Cnf {
+        let mut cnf = Cnf::new();
+
+        let literal = cnf.encode_with(expression, encoding, Polarity::Positive);
+
+        cnf.add_clause(vec![literal]);
+
+        cnf
+    }
+
+    /// Builds an equivalent clause database over the variables of
+    /// `expression` only, by distributing `|` over `&`.
+    pub fn from_expression_direct (expression: &Expression) -> Cnf {
+        let mut cnf = Cnf::new();
+
+        for clause in direct::clauses(&mut cnf, expression, true) {
+            cnf.add_clause(clause);
+        }
+
+        cnf
+    }
+
+    /// Returns the variable named `name`, creating it on first use.
+    pub fn variable<S: AsRef<str>> (&mut self, name: S) -> usize {
+        let name = name.as_ref();
+
+        match self.indices.get(name) {
+            Some (&variable) => variable,
+            None => {
+                let variable = self.names.len();
+
+                self.names.push(Some(name.to_string()));
+                self.indices.insert(name.to_string(), variable);
+
+                variable
+            }
+        }
+    }
+
+    /// Creates an auxiliary variable that has no name.
+    pub fn fresh (&mut self) -> usize {
+        self.names.push(None);
+
+        self.names.len() - 1
+    }
+
+    pub fn add_clause (&mut self, clause: Clause) {
+        self.clauses.push(clause);
+    }
+
+    /// Adds clauses allowing at most `bound` of `literals` to be true,
+    /// through auxiliary counter variables.
+    pub fn add_at_most (&mut self, literals: &[Literal], bound: usize) {
+        cardinality::at_most(self, literals, bound);
+    }
+
+    /// Encodes the total weight of the true `weighted` literals, returning
+    /// an output literal for every reachable sum up to `cap`, implied by
+    /// the true literals weighing at least that sum (sums above `cap`
+    /// count as `cap`). Falsifying the outputs from `s` up keeps the total
+    /// below `s`. Returns `None`, adding nothing, when the encoding would
+    /// need more than [`WEIGHT_COUNTER_LIMIT`] clauses.
+    pub fn add_weight_counter (&mut self, weighted: &[(Literal, u64)], cap: u64) -> Option<BTreeMap<u64, Literal>> {
+        cardinality::weight_counter(self, weighted, cap)
+    }
+
+    pub fn variables (&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn clauses (&self) -> &[Clause] {
+        &self.clauses
+    }
+
+    pub fn name (&self, variable: usize) -> Option<&str> {
+        self.names.get(variable)?.as_deref()
+    }
+
+    pub fn index (&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Iterates over the named variables in index order.
+    pub fn names (&self) -> impl Iterator<Item = (usize, &str)> {
+        self.names.iter()
+            .enumerate()
+            .filter_map(|(variable, name)| Some((variable, name.as_deref()?)))
+    }
+
+    /// Keeps the named variables of a full assignment.
+    pub fn model (&self, values: &[bool]) -> Model {
+        self.names.iter()
+            .zip(values)
+            .filter_map(|(name, &value)| {
+                name.as_ref().map(|name| (name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Returns a literal equivalent to `expression`, adding the clauses
+    /// that define every auxiliary variable introduced along the way.
+    pub fn encode (&mut self, expression: &Expression) -> Literal {
+        tseitin::encode(self, expression, Encoding::Tseitin, Polarity::Both)
+    }
+
+    /// Like [`Cnf::encode`], but the returned literal is only guaranteed to
+    /// agree with `expression` in the given `polarity` when `encoding` is
+    /// [`Encoding::PlaistedGreenbaum`].
+    pub fn encode_with (&mut self, expression: &Expression, encoding: Encoding, polarity: Polarity) -> Literal {
+        tseitin::encode(self, expression, encoding, polarity)
+    }
+
+    pub(crate) fn truth (&mut self) -> Literal {
+        match self.truth {
+            Some (literal) => literal,
+            None => {
+                let literal = Literal::positive(self.fresh());
+
+                self.add_clause(vec![literal]);
+                self.truth = Some(literal);
+
+                literal
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn literal_should_pack_variable_and_sign () {
+        let literal = Literal::new(3, false);
+
+        assert_eq!(literal.variable(), 3);
+        assert!(!literal.is_positive());
+        assert_eq!(!literal, Literal::positive(3));
+        assert_eq!(!!literal, literal);
+    }
+
+    #[test]
+    fn should_reuse_named_variables () {
+        let expression = Expression::And(
+            Box::new(Expression::var("a")),
+            Box::new(Expression::Not(Box::new(Expression::var("a")))),
+        );
+
+        let cnf = Cnf::from_expression(&expression);
+
+        assert_eq!(cnf.index("a"), Some(0));
+        assert_eq!(cnf.variables(), 2);
+        assert_eq!(cnf.name(1), None);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::expression::Expression;
+
+use super::{Cnf, Literal};
+
+/// How auxiliary variables are tied to the subformulas they stand for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Every auxiliary variable is equivalent to its subformula.
+    #[default]
+    Tseitin,
+
+    /// Plaisted–Greenbaum: only the implications required by the polarity
+    /// in which a subformula occurs are emitted, roughly halving the clauses.
+    PlaistedGreenbaum,
+}
+
+/// The polarity in which a subformula occurs in the asserted formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    Positive,
+    Negative,
+    Both,
+}
+
+impl Polarity {
+    fn flip (self) -> Polarity {
+        match self {
+            Polarity::Positive => Polarity::Negative,
+            Polarity::Negative => Polarity::Positive,
+            Polarity::Both => Polarity::Both,
+        }
+    }
+
+    fn positive (self) -> bool {
+        self != Polarity::Negative
+    }
+
+    fn negative (self) -> bool {
+        self != Polarity::Positive
+    }
+}
+
+/// Introduces an auxiliary variable for every binary node of
+/// `expression`, producing a number of clauses linear in its size. The
+/// returned literal implies the expression when `polarity` is positive and
+/// is implied by it when `polarity` is negative.
+pub fn encode (cnf: &mut Cnf, expression: &Expression, encoding: Encoding, polarity: Polarity) -> Literal {
+    let polarity = match encoding {
+        Encoding::Tseitin => Polarity::Both,
+        Encoding::PlaistedGreenbaum => polarity,
+    };
+
+    match expression {
+        Expression::Var (name) => Literal::positive(cnf.variable(name)),
+
+        Expression::True => cnf.truth(),
+        Expression::False => !cnf.truth(),
+
+        Expression::Not (inner) => !encode(cnf, inner, encoding, polarity.flip()),
+
+        Expression::And (left, right) => {
+            let left = encode(cnf, left, encoding, polarity);
+            let right = encode(cnf, right, encoding, polarity);
+            let output = Literal::positive(cnf.fresh());
+
+            if polarity.positive() {
+                cnf.add_clause(vec![!output, left]);
+                cnf.add_clause(vec![!output, right]);
+            }
+
+            if polarity.negative() {
+                cnf.add_clause(vec![output, !left, !right]);
+            }
+
+            output
+        }
+
+        Expression::Or (left, right) => {
+            let left = encode(cnf, left, encoding, polarity);
+            let right = encode(cnf, right, encoding, polarity);
+            let output = Literal::positive(cnf.fresh());
+
+            if polarity.positive() {
+                cnf.add_clause(vec![!output, left, right]);
+            }
+
+            if polarity.negative() {
+                cnf.add_clause(vec![output, !left]);
+                cnf.add_clause(vec![output, !right]);
+            }
+
+            output
+        }
+
+        Expression::Xor (left, right) => {
+            let left = encode(cnf, left, encoding, Polarity::Both);
+            let right = encode(cnf, right, encoding, Polarity::Both);
+            let output = Literal::positive(cnf.fresh());
+
+            if polarity.positive() {
+                cnf.add_clause(vec![!output, left, right]);
+                cnf.add_clause(vec![!output, !left, !right]);
+            }
+
+            if polarity.negative() {
+                cnf.add_clause(vec![output, !left, right]);
+                cnf.add_clause(vec![output, left, !right]);
+            }
+
+            output
+        }
+
+        Expression::Implies (left, right) => {
+            let left = encode(cnf, left, encoding, polarity.flip());
+            let right = encode(cnf, right, encoding, polarity);
+            let output = Literal::positive(cnf.fresh());
+
+            if polarity.positive() {
+                cnf.add_clause(vec![!output, !left, right]);
+            }
+
+            if polarity.negative() {
+                cnf.add_clause(vec![output, left]);
+                cnf.add_clause(vec![output, !right]);
+            }
+
+            output
+        }
+
+        Expression::Iff (left, right) => {
+            let left = encode(cnf, left, encoding, Polarity::Both);
+            let right = encode(cnf, right, encoding, Polarity::Both);
+            let output = Literal::positive(cnf.fresh());
+
+            if polarity.positive() {
+                cnf.add_clause(vec![!output, !left, right]);
+                cnf.add_clause(vec![!output, left, !right]);
+            }
+
+            if polarity.negative() {
+                cnf.add_clause(vec![output, left, right]);
+                cnf.add_clause(vec![output, !left, !right]);
+            }
+
+            output
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::satisfability::{CdclSolver, Model};
+    use crate::testing::{Random, evaluate};
+
+    fn satisfiable_by_enumeration (expression: &Expression, names: &[&str], expectative: bool) -> bool {
+        (0..1u32 << names.len()).any(|bits| {
+            let model: Model = names.iter()
+                .enumerate()
+                .map(|(index, name)| (name.to_string(), bits & (1 << index) != 0))
+                .collect();
+
+            evaluate(expression, &model) == expectative
+        })
+    }
+
+    fn size (expression: &Expression) -> usize {
+        match expression {
+            Expression::Not (inner) => 1 + size(inner),
+            Expression::And (left, right) | Expression::Or (left, right) | Expression::Xor (left, right)
+            | Expression::Implies (left, right) | Expression::Iff (left, right) => {
+                1 + size(left) + size(right)
+            }
+            _ => 1,
+        }
+    }
+
+    #[test]
+    fn should_map_auxiliary_variables_back_to_names () {
+        let expression = Expression::Or(
+            Box::new(Expression::var("left")),
+            Box::new(Expression::var("right")),
+        );
+
+        let cnf = Cnf::from_expression(&expression);
+
+        assert_eq!(cnf.variables(), 3);
+        assert_eq!(cnf.name(0), Some("left"));
+        assert_eq!(cnf.name(1), Some("right"));
+        assert_eq!(cnf.name(2), None);
+        assert_eq!(cnf.names().collect::<Vec<_>>(), vec![(0, "left"), (1, "right")]);
+    }
+
+    #[test]
+    fn plaisted_greenbaum_should_emit_fewer_clauses () {
+        let expression = Expression::And(
+            Box::new(Expression::Or(
+                Box::new(Expression::var("a")),
+                Box::new(Expression::var("b")),
+            )),
+            Box::new(Expression::Not(Box::new(Expression::And(
+                Box::new(Expression::var("a")),
+                Box::new(Expression::var("c")),
+            )))),
+        );
+
+        let tseitin = Cnf::from_expression_with(&expression, Encoding::Tseitin);
+        let plaisted_greenbaum = Cnf::from_expression_with(&expression, Encoding::PlaistedGreenbaum);
+
+        assert_eq!(tseitin.clauses().len(), 10);
+        assert_eq!(plaisted_greenbaum.clauses().len(), 5);
+    }
+
+    #[test]
+    fn encodings_should_be_equisatisfiable_and_linear () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0x6A09E667F3BCC909);
+
+        for _ in 0..300 {
+            let expression = random.expression(&names, 6);
+
+            for encoding in [Encoding::Tseitin, Encoding::PlaistedGreenbaum] {
+                for expectative in [true, false] {
+                    let mut cnf = Cnf::new();
+
+                    let polarity = if expectative { Polarity::Positive } else { Polarity::Negative };
+                    let literal = cnf.encode_with(&expression, encoding, polarity);
+
+                    cnf.add_clause(vec![if expectative { literal } else { !literal }]);
+
+                    assert!(cnf.clauses().len() <= 4 * size(&expression) + 2);
+
+                    let solution = CdclSolver::from_cnf(&cnf).solve();
+
+                    assert_eq!(
+                        solution.is_some(),
+                        satisfiable_by_enumeration(&expression, &names, expectative),
+                        "{:?} {:?}", encoding, expression,
+                    );
+
+                    if let Some (values) = solution {
+                        assert_eq!(evaluate(&expression, &cnf.model(&values)), expectative);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::lexer::{LexerError, Span, Token};
+use crate::parser::ParserError;
+
+/// A compiler-style error report pointing into the parsed string.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    message: String,
+    span: Span,
+    label: String,
+    note: Option<(Span, String)>,
+}
+
+impl Diagnostic {
+    pub fn new<S: Into<String>, L: Into<String>> (message: S, span: Span, label: L) -> Diagnostic {
+        Diagnostic {
+            message: message.into(),
+            span,
+            label: label.into(),
+            note: None,
+        }
+    }
+
+    /// Adds a secondary location, rendered with `-` instead of `^`.
+    pub fn with_note<S: Into<String>> (mut self, span: Span, note: S) -> Diagnostic {
+        self.note = Some((span, note.into()));
+        self
+    }
+
+    pub fn message (&self) -> &str {
+        &self.message
+    }
+
+    /// Renders the report against the string the error was found in:
+    ///
+    /// ```text
+    /// error: unclosed parenthesis
+    ///  --> 1:7
+    ///   |
+    /// 1 | (a & b
+    ///   |       ^ expected `)`
+    ///   | - missing `)` opened here
+    /// ```
+    ///
+    /// A note on another line than the error repeats that line, numbered.
+    pub fn render (&self, source: &str) -> String {
+        let (line, column) = location(source, self.span.start);
+        let note_line = self.note.as_ref().map(|(span, _)| location(source, span.start).0);
+
+        let width = (line.max(note_line.unwrap_or(0)) + 1).to_string().len();
+        let gutter = " ".repeat(width);
+
+        let numbered = |line: usize| {
+            format!("{:>width$} | {}\n", line + 1, source.lines().nth(line).unwrap_or(""), width = width)
+        };
+
+        let mut output = format!("error: {}\n", self.message);
+
+        output.push_str(&format!("{}--> {}:{}\n", gutter, line + 1, column + 1));
+        output.push_str(&format!("{} |\n", gutter));
+        output.push_str(&numbered(line));
+        output.push_str(&format!("{} | {}\n", gutter, underline(source, self.span, '^', &self.label)));
+
+        if let (Some ((span, note)), Some (note_line)) = (&self.note, note_line) {
+            if note_line != line {
+                output.push_str(&numbered(note_line));
+            }
+
+            output.push_str(&format!("{} | {}\n", gutter, underline(source, *span, '-', note)));
+        }
+
+        output
+    }
+}
+
+/// Zero-based line and character column of a byte offset.
+fn location (source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count();
+    let start = before.rfind('\n').map_or(0, |index| index + 1);
+
+    (line, before[start..].chars().count())
+}
+
+fn underline (source: &str, span: Span, marker: char, label: &str) -> String {
+    let (_, column) = location(source, span.start);
+    let width = source.get(span.start..span.end).map_or(0, |text| text.chars().count()).max(1);
+
+    format!("{}{} {}", " ".repeat(column), marker.to_string().repeat(width), label)
+}
+
+/// Names `token`, quoting its spelling at `span` in `source` when given,
+/// as operators have several.
+fn describe (token: &Token, span: Span, source: Option<&str>) -> String {
+    match (token, source.and_then(|source| source.get(span.start..span.end))) {
+        (Token::Var (name), _) => format!("variable `{}`", name),
+        (_, Some (text)) => format!("`{}`", text),
+        (token, None) => canonical(token),
+    }
+}
+
+fn canonical (token: &Token) -> String {
+    match token {
+        Token::Var (name) => format!("variable `{}`", name),
+        Token::And => "`&`".to_string(),
+        Token::Or => "`|`".to_string(),
+        Token::Not => "`¬`".to_string(),
+        Token::Xor => "`^`".to_string(),
+        Token::LParen => "`(`".to_string(),
+        Token::RParen => "`)`".to_string(),
+        Token::Implies => "`->`".to_string(),
+        Token::Iff => "`<->`".to_string(),
+        Token::Prime => "`'`".to_string(),
+        Token::True => "`true`".to_string(),
+        Token::False => "`false`".to_string(),
+    }
+}
+
+impl From<&LexerError> for Diagnostic {
+    fn from (error: &LexerError) -> Diagnostic {
+        match error {
+            LexerError::UnexpectedCharacter (ch, span) => {
+                Diagnostic::new(format!("unexpected character `{}`", ch), *span, "not a valid token")
+            }
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Reports `error` found in `source`, quoting tokens as they are spelled
+    /// there instead of by their canonical operator.
+    pub fn from_source (error: &ParserError, source: &str) -> Diagnostic {
+        parser_diagnostic(error, Some(source))
+    }
+}
+
+impl From<&ParserError> for Diagnostic {
+    fn from (error: &ParserError) -> Diagnostic {
+        parser_diagnostic(error, None)
+    }
+}
+
+fn parser_diagnostic (error: &ParserError, source: Option<&str>) -> Diagnostic {
+    match error {
+        ParserError::Lexer (error) => Diagnostic::from(error),
+
+        ParserError::UnclosedParen (opened, found) => {
+            Diagnostic::new("unclosed parenthesis", *found, "expected `)`")
+                .with_note(*opened, "missing `)` opened here")
+        }
+
+        ParserError::UnexpectedToken (token, span) => {
+            Diagnostic::new(format!("unexpected {}", describe(token, *span, source)), *span, "expected a variable, a constant, `¬` or `(`")
+        }
+
+        ParserError::ExpectedEndOfInput (Token::RParen, span) => {
+            Diagnostic::new("unexpected `)`", *span, "no matching `(`")
+        }
+
+        ParserError::ExpectedEndOfInput (token, span) => {
+            Diagnostic::new(format!("unexpected {}", describe(token, *span, source)), *span, "expected an operator or the end of the formula")
+        }
+
+        ParserError::UnexpectedEndOfInput (span) => {
+            Diagnostic::new("unexpected end of input", *span, "expected a variable, a constant, `¬` or `(`")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lexer::Dialect;
+    use crate::{parse, parse_with};
+
+    fn render (source: &str) -> String {
+        Diagnostic::from_source(&parse(source).unwrap_err(), source).render(source)
+    }
+
+    #[test]
+    fn should_point_at_unexpected_character () {
+        assert_eq!(
+            render("¬a & $b"),
+            concat!(
+                "error: unexpected character `$`\n",
+                " --> 1:6\n",
+                "  |\n",
+                "1 | ¬a & $b\n",
+                "  |      ^ not a valid token\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn should_point_at_opening_paren () {
+        assert_eq!(
+            render("(a & b"),
+            concat!(
+                "error: unclosed parenthesis\n",
+                " --> 1:7\n",
+                "  |\n",
+                "1 | (a & b\n",
+                "  |       ^ expected `)`\n",
+                "  | - missing `)` opened here\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn should_number_the_line_of_a_note_elsewhere () {
+        assert_eq!(
+            render("(a &\n\n\n\n\n\n\n\n\nb"),
+            concat!(
+                "error: unclosed parenthesis\n",
+                "  --> 10:2\n",
+                "   |\n",
+                "10 | b\n",
+                "   |  ^ expected `)`\n",
+                " 1 | (a &\n",
+                "   | - missing `)` opened here\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn should_quote_operators_as_written () {
+        assert_eq!(
+            render("a && || b"),
+            concat!(
+                "error: unexpected `||`\n",
+                " --> 1:6\n",
+                "  |\n",
+                "1 | a && || b\n",
+                "  |      ^^ expected a variable, a constant, `¬` or `(`\n",
+            ),
+        );
+
+        assert_eq!(
+            Diagnostic::from_source(&parse_with("a and and b", Dialect::Keywords).unwrap_err(), "a and and b").message(),
+            "unexpected `and`",
+        );
+        assert_eq!(Diagnostic::from(&parse_with("a ~", Dialect::Symbolic).unwrap_err()).message(), "unexpected `¬`");
+        assert_eq!(Diagnostic::from_source(&parse_with("a ~", Dialect::Symbolic).unwrap_err(), "a ~").message(), "unexpected `~`");
+    }
+
+    #[test]
+    fn should_point_at_unmatched_paren () {
+        assert_eq!(
+            render("a)"),
+            concat!(
+                "error: unexpected `)`\n",
+                " --> 1:2\n",
+                "  |\n",
+                "1 | a)\n",
+                "  |  ^ no matching `(`\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn should_underline_whole_token () {
+        assert_eq!(
+            render("a bc d"),
+            concat!(
+                "error: unexpected variable `bc`\n",
+                " --> 1:3\n",
+                "  |\n",
+                "1 | a bc d\n",
+                "  |   ^^ expected an operator or the end of the formula\n",
+            ),
+        );
+    }
+}
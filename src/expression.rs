@@ -1,3 +1,6 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub enum Expression {
     Var (String),
@@ -8,10 +11,28 @@ pub enum Expression {
 
     Xor (Box<Expression>, Box<Expression>),
 
+    Implies (Box<Expression>, Box<Expression>),
+    Iff (Box<Expression>, Box<Expression>),
+
     True,
     False,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum EvaluationError {
+    UnassignedVariable (String),
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvaluationError::UnassignedVariable (name) => write!(formatter, "variable `{}` is not assigned", name),
+        }
+    }
+}
+
+impl std::error::Error for EvaluationError {}
+
 impl PartialEq for Expression {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -27,6 +48,21 @@ impl PartialEq for Expression {
                 Self::And(right_left, right_right)
             ) => (left_left == right_left && left_right == right_right) || (left_left == right_right && left_right == right_left),
 
+            (
+                Self::Implies(left_left, left_right),
+                Self::Implies(right_left, right_right)
+            ) => left_left == right_left && left_right == right_right,
+
+            (
+                Self::Xor(left_left, left_right),
+                Self::Xor(right_left, right_right)
+            ) => (left_left == right_left && left_right == right_right) || (left_left == right_right && left_right == right_left),
+
+            (
+                Self::Iff(left_left, left_right),
+                Self::Iff(right_left, right_right)
+            ) => (left_left == right_left && left_right == right_right) || (left_left == right_right && left_right == right_left),
+
             (Self::Var(a), Self::Var(b)) => a == b,
 
             (Expression::True, Expression::True) => true,
@@ -38,16 +74,120 @@ impl PartialEq for Expression {
 }
 
 impl Expression {
+    /// Names of the variables occurring in the expression, in sorted order.
+    pub fn variables (&self) -> BTreeSet<String> {
+        let mut variables = BTreeSet::new();
+
+        self.collect_variables(&mut variables);
+
+        variables
+    }
+
+    fn collect_variables (&self, variables: &mut BTreeSet<String>) {
+        match self {
+            Expression::Var (name) => {
+                variables.insert(name.clone());
+            }
+
+            Expression::Not (inner) => inner.collect_variables(variables),
+
+            Expression::And (left, right)
+            | Expression::Or (left, right)
+            | Expression::Xor (left, right)
+            | Expression::Implies (left, right)
+            | Expression::Iff (left, right) => {
+                left.collect_variables(variables);
+                right.collect_variables(variables);
+            }
+
+            Expression::True | Expression::False => {}
+        }
+    }
+
+    /// Value of the expression when every variable takes its value from
+    /// `assignment`.
+    pub fn evaluate (&self, assignment: &BTreeMap<String, bool>) -> Result<bool, EvaluationError> {
+        match self {
+            Expression::Var (name) => assignment.get(name)
+                .copied()
+                .ok_or_else(|| EvaluationError::UnassignedVariable(name.clone())),
+
+            Expression::Not (inner) => Ok(!inner.evaluate(assignment)?),
+            Expression::And (left, right) => Ok(left.evaluate(assignment)? && right.evaluate(assignment)?),
+            Expression::Or (left, right) => Ok(left.evaluate(assignment)? || right.evaluate(assignment)?),
+            Expression::Xor (left, right) => Ok(left.evaluate(assignment)? != right.evaluate(assignment)?),
+            Expression::Implies (left, right) => Ok(!left.evaluate(assignment)? || right.evaluate(assignment)?),
+            Expression::Iff (left, right) => Ok(left.evaluate(assignment)? == right.evaluate(assignment)?),
+
+            Expression::True => Ok(true),
+            Expression::False => Ok(false),
+        }
+    }
+
+    /// Substitutes the assigned variables and simplifies what is left with
+    /// the laws of [`Expression::and`], [`Expression::or`] and
+    /// [`Expression::not`]. Fully assigned expressions reduce to a constant.
+    pub fn partial_evaluate (&self, assignment: &BTreeMap<String, bool>) -> Expression {
+        match self {
+            Expression::Var (name) => match assignment.get(name) {
+                Some (true) => Expression::True,
+                Some (false) => Expression::False,
+                None => self.clone(),
+            },
+
+            Expression::Not (inner) => Expression::not(inner.partial_evaluate(assignment)),
+
+            Expression::And (left, right) => Expression::and(
+                left.partial_evaluate(assignment),
+                right.partial_evaluate(assignment),
+            ),
+
+            Expression::Or (left, right) => Expression::or(
+                left.partial_evaluate(assignment),
+                right.partial_evaluate(assignment),
+            ),
+
+            Expression::Xor (left, right) => {
+                match (left.partial_evaluate(assignment), right.partial_evaluate(assignment)) {
+                    (Expression::False, other) | (other, Expression::False) => other,
+                    (Expression::True, other) | (other, Expression::True) => Expression::not(other),
+                    (left, right) => Expression::xor(left, right),
+                }
+            }
+
+            Expression::Implies (left, right) => {
+                match (left.partial_evaluate(assignment), right.partial_evaluate(assignment)) {
+                    (Expression::False, _) | (_, Expression::True) => Expression::True,
+                    (Expression::True, right) => right,
+                    (left, Expression::False) => Expression::not(left),
+                    (left, right) => Expression::implies(left, right),
+                }
+            }
+
+            Expression::Iff (left, right) => {
+                match (left.partial_evaluate(assignment), right.partial_evaluate(assignment)) {
+                    (Expression::True, other) | (other, Expression::True) => other,
+                    (Expression::False, other) | (other, Expression::False) => Expression::not(other),
+                    (left, right) => Expression::iff(left, right),
+                }
+            }
+
+            Expression::True => Expression::True,
+            Expression::False => Expression::False,
+        }
+    }
+
     pub fn from_expressions<I: IntoIterator<Item = Expression>> (expressions: I) -> Option<Expression> {
         expressions.into_iter().reduce(|left, right| {
             Expression::And(Box::new(left), Box::new(right))
         })
     }
 
-pub     fn var<S: Into<String>> (string: S) -> Expression {
+    pub fn var<S: Into<String>> (string: S) -> Expression {
         Expression::Var(string.into())
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn not (inner: Expression) -> Expression {
         match inner {
             Expression::True => Expression::False,
@@ -136,6 +276,20 @@ pub     fn var<S: Into<String>> (string: S) -> Expression {
         )
     }
 
+    pub fn implies (left: Expression, right: Expression) -> Expression {
+        Expression::Implies(
+            Box::new(left),
+            Box::new(right),
+        )
+    }
+
+    pub fn iff (left: Expression, right: Expression) -> Expression {
+        Expression::Iff(
+            Box::new(left),
+            Box::new(right),
+        )
+    }
+
     pub fn simplify (self) -> Expression {
         match self {
             Expression::Xor (left, right) => {
@@ -158,6 +312,36 @@ pub     fn var<S: Into<String>> (string: S) -> Expression {
                 )
             },
 
+            Expression::Implies (left, right) => {
+                let left = left.simplify();
+                let right = right.simplify();
+
+                Expression::or(
+                    Expression::not(left),
+                    right,
+                )
+            }
+
+            Expression::Iff (left, right) => {
+                let left = left.simplify();
+                let right = right.simplify();
+
+                Expression::or(
+                    Expression::and(
+                        left.clone(),
+                        right.clone(),
+                    ),
+                    Expression::and(
+                        Expression::not(left),
+                        Expression::not(right),
+                    ),
+                )
+            }
+
+            Expression::Not (inner) => Expression::not(inner.simplify()),
+            Expression::And (left, right) => Expression::and(left.simplify(), right.simplify()),
+            Expression::Or (left, right) => Expression::or(left.simplify(), right.simplify()),
+
             expression => expression,
         }
     }
@@ -256,6 +440,23 @@ mod test {
         }
     }
 
+    #[test]
+    fn should_compare_commutative_operators_either_way () {
+        let xor = |left: &str, right: &str| Expression::Xor(Box::new(Expression::var(left)), Box::new(Expression::var(right)));
+        let iff = |left: &str, right: &str| Expression::Iff(Box::new(Expression::var(left)), Box::new(Expression::var(right)));
+        let implies = |left: &str, right: &str| Expression::Implies(Box::new(Expression::var(left)), Box::new(Expression::var(right)));
+
+        assert_eq!(xor("a", "b"), xor("a", "b"));
+        assert_eq!(xor("a", "b"), xor("b", "a"));
+        assert_ne!(xor("a", "b"), xor("a", "c"));
+        assert_ne!(xor("a", "b"), iff("a", "b"));
+
+        assert_eq!(iff("a", "b"), iff("b", "a"));
+
+        assert_eq!(implies("a", "b"), implies("a", "b"));
+        assert_ne!(implies("a", "b"), implies("b", "a"));
+    }
+
     #[test]
     fn should_not_be_equal () {
         let comparisons = [
@@ -367,6 +568,135 @@ mod test {
         }
     }
 
+    #[test]
+    fn should_collect_sorted_variables () {
+        let expression = Expression::Or(
+            Box::new(Expression::var("b")),
+            Box::new(Expression::And(
+                Box::new(Expression::var("a")),
+                Box::new(Expression::Not(Box::new(Expression::var("b")))),
+            )),
+        );
+
+        assert_eq!(
+            expression.variables().into_iter().collect::<Vec<_>>(),
+            vec!["a".to_string(), "b".to_string()],
+        );
+    }
+
+    #[test]
+    fn should_evaluate_under_assignment () {
+        let expression = Expression::implies(
+            Expression::var("a"),
+            Expression::Xor(
+                Box::new(Expression::var("b")),
+                Box::new(Expression::True),
+            ),
+        );
+
+        let assignment = BTreeMap::from([("a".to_string(), true), ("b".to_string(), true)]);
+
+        assert_eq!(expression.evaluate(&assignment), Ok(false));
+
+        let assignment = BTreeMap::from([("a".to_string(), true)]);
+
+        assert_eq!(
+            expression.evaluate(&assignment),
+            Err(EvaluationError::UnassignedVariable("b".to_string())),
+        );
+    }
+
+    #[test]
+    fn should_partially_evaluate_to_residual () {
+        let expression = Expression::And(
+            Box::new(Expression::Or(
+                Box::new(Expression::var("a")),
+                Box::new(Expression::var("b")),
+            )),
+            Box::new(Expression::iff(
+                Expression::var("c"),
+                Expression::var("a"),
+            )),
+        );
+
+        let assignment = BTreeMap::from([("a".to_string(), false)]);
+
+        assert_eq!(
+            expression.partial_evaluate(&assignment),
+            Expression::And(
+                Box::new(Expression::var("b")),
+                Box::new(Expression::Not(Box::new(Expression::var("c")))),
+            ),
+        );
+
+        let assignment = BTreeMap::from([
+            ("a".to_string(), true),
+            ("b".to_string(), false),
+            ("c".to_string(), true),
+        ]);
+
+        assert_eq!(expression.partial_evaluate(&assignment), Expression::True);
+    }
+
+    #[test]
+    fn should_partially_evaluate_xor_implies_and_iff () {
+        let assignment = BTreeMap::from([("a".to_string(), true), ("b".to_string(), false)]);
+
+        let residual = |expression: Expression| expression.partial_evaluate(&assignment);
+
+        let c = || Expression::var("c");
+        let not_c = || Expression::Not(Box::new(Expression::var("c")));
+
+        assert_eq!(residual(Expression::xor(Expression::var("a"), c())), not_c());
+        assert_eq!(residual(Expression::xor(c(), Expression::var("b"))), c());
+
+        assert_eq!(residual(Expression::implies(Expression::var("a"), c())), c());
+        assert_eq!(residual(Expression::implies(c(), Expression::var("b"))), not_c());
+        assert_eq!(residual(Expression::implies(Expression::var("b"), c())), Expression::True);
+        assert_eq!(residual(Expression::implies(c(), Expression::var("a"))), Expression::True);
+
+        assert_eq!(residual(Expression::iff(Expression::var("a"), c())), c());
+        assert_eq!(residual(Expression::iff(c(), Expression::var("b"))), not_c());
+
+        assert_eq!(
+            residual(Expression::iff(c(), Expression::var("d"))),
+            Expression::iff(c(), Expression::var("d")),
+        );
+    }
+
+    #[test]
+    fn should_fold_constants_when_partially_evaluating () {
+        let assignment = BTreeMap::from([("a".to_string(), false)]);
+
+        let expression = Expression::Or(
+            Box::new(Expression::And(Box::new(Expression::True), Box::new(Expression::var("b")))),
+            Box::new(Expression::Xor(Box::new(Expression::var("a")), Box::new(Expression::False))),
+        );
+
+        assert_eq!(expression.partial_evaluate(&assignment), Expression::var("b"));
+
+        let expression = Expression::Iff(
+            Box::new(Expression::Implies(Box::new(Expression::var("a")), Box::new(Expression::var("b")))),
+            Box::new(Expression::Not(Box::new(Expression::False))),
+        );
+
+        assert_eq!(expression.partial_evaluate(&assignment), Expression::True);
+
+        let expression = Expression::And(
+            Box::new(Expression::var("b")),
+            Box::new(Expression::Or(Box::new(Expression::var("a")), Box::new(Expression::False))),
+        );
+
+        assert_eq!(expression.partial_evaluate(&assignment), Expression::False);
+    }
+
+    #[test]
+    fn evaluation_errors_should_name_the_variable () {
+        let error = Expression::var("b").evaluate(&BTreeMap::new()).unwrap_err();
+
+        assert_eq!(error.to_string(), "variable `b` is not assigned");
+    }
+
     #[test]
     fn should_optimize_not () {
         let expression = Expression::not(Expression::Var("a".to_string()));
@@ -766,6 +1096,46 @@ mod test {
         }
     }
 
+    #[test]
+    fn should_simplify_implies () {
+        let expression = Expression::implies(
+            Expression::var("a"),
+            Expression::var("b"),
+        ).simplify();
+
+        assert_eq!(expression, Expression::Or(
+            Box::new(
+                Expression::Not(
+                    Box::new(Expression::Var("a".to_string()))
+                )
+            ),
+            Box::new(Expression::Var("b".to_string())),
+        ));
+    }
+
+    #[test]
+    fn should_simplify_iff_with_itself () {
+        let expression = Expression::iff(
+            Expression::var("a"),
+            Expression::var("a"),
+        ).simplify();
+
+        assert_eq!(expression, Expression::True);
+    }
+
+    #[test]
+    fn should_simplify_nested_constants () {
+        let expression = Expression::And(
+            Box::new(Expression::var("a")),
+            Box::new(Expression::Or(
+                Box::new(Expression::var("b")),
+                Box::new(Expression::Not(Box::new(Expression::False))),
+            )),
+        ).simplify();
+
+        assert_eq!(expression, Expression::Var("a".to_string()));
+    }
+
     #[test]
     #[ignore]
     fn should_optimize_or_with_anti_distributive_law () {
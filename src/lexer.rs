@@ -1,7 +1,7 @@
-use std::str::Chars;
+use std::str::CharIndices;
 use std::iter::Peekable;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Token {
     Var(String),
     And,
@@ -10,23 +10,127 @@ pub enum Token {
     LParen,
     RParen,
     Xor,
+    Implies,
+    Iff,
+
+    /// Postfix negation, as in `a'`.
+    Prime,
+
+    True,
+    False,
+}
+
+/// The set of operator spellings the lexer recognises.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Dialect {
+    /// Only `¬`, `&`, `|`, `^`, `->`/`→` and `<->`/`↔`, with `⊤` and `⊥`
+    /// as constants.
+    Strict,
+
+    /// Also accepts `!`, `~` and `-` for negation, `&&`, `∧` and `*` for
+    /// conjunction, `||`, `∨` and `+` for disjunction, `⊕` for exclusive or
+    /// and postfix `'` negation, and reserves `true`, `false`, `1` and `0`
+    /// (in any case) as constants.
+    #[default]
+    Symbolic,
+
+    /// Also reserves `and`, `or`, `not` and `xor` (in lower or upper case)
+    /// as keywords, so they can no longer name variables.
+    Keywords,
+}
+
+/// Byte range of a token in the lexed string.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new (start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum LexerError {
-    UnexpectedCharacter (char)
+    UnexpectedCharacter (char, Span)
 }
 
-pub type LexerResult = Result<Token, LexerError>;
+pub type LexerResult = Result<(Token, Span), LexerError>;
 
 pub struct Lexer<'a> {
-    chars: Peekable<Chars<'a>>
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    dialect: Dialect,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new (string: &'a str) -> Lexer<'a> {
+        Lexer::with_dialect(string, Dialect::default())
+    }
+
+    pub fn with_dialect (string: &'a str, dialect: Dialect) -> Lexer<'a> {
         Lexer {
-            chars: string.chars().peekable()
+            source: string,
+            chars: string.char_indices().peekable(),
+            dialect,
+        }
+    }
+
+    fn symbolic (&self) -> bool {
+        self.dialect != Dialect::Strict
+    }
+
+    fn keyword (&self, word: &str) -> Option<Token> {
+        if self.dialect != Dialect::Keywords {
+            return None;
+        }
+
+        match word.to_ascii_lowercase().as_str() {
+            "and" => Some(Token::And),
+            "or" => Some(Token::Or),
+            "not" => Some(Token::Not),
+            "xor" => Some(Token::Xor),
+            _ => None,
+        }
+    }
+
+    fn constant (&self, word: &str) -> Option<Token> {
+        if !self.symbolic() {
+            return None;
+        }
+
+        match word.to_ascii_lowercase().as_str() {
+            "true" | "1" => Some(Token::True),
+            "false" | "0" => Some(Token::False),
+            _ => None,
+        }
+    }
+
+    pub fn source (&self) -> &'a str {
+        self.source
+    }
+
+    /// Consumes `expected` if the upcoming characters spell it out.
+    fn follows (&mut self, expected: &str) -> bool {
+        let offset = self.offset();
+
+        if self.source[offset..].starts_with(expected) {
+            for _ in expected.chars() {
+                self.chars.next();
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+
+    fn offset (&mut self) -> usize {
+        match self.chars.peek() {
+            Some (&(offset, _)) => offset,
+            None => self.source.len(),
         }
     }
 }
@@ -37,28 +141,60 @@ impl<'a> Iterator for Lexer<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.chars.next() {
-                Some (ch) if ch.is_ascii_whitespace() => continue,
-
-                Some (ch) => break match ch {
-                    '^' => Some(Ok(Token::Xor)),
-                    '|' => Some(Ok(Token::Or)),
-                    '&' => Some(Ok(Token::And)),
-                    '¬' => Some(Ok(Token::Not)),
-                    '(' => Some(Ok(Token::LParen)),
-                    ')' => Some(Ok(Token::RParen)),
-
-                    ch if ch.is_ascii_alphanumeric() => {
-                        let mut string = String::from(ch);
-
-                        loop {
-                            match self.chars.next_if(|ch| ch.is_ascii_alphanumeric()) {
-                                Some (ch) => string.push(ch),
-                                _ => break Some(Ok(Token::Var(string))),
+                Some ((_, ch)) if ch.is_ascii_whitespace() => continue,
+
+                Some ((start, ch)) => {
+                    let token = match ch {
+                        '^' => Token::Xor,
+                        '|' => Token::Or,
+                        '&' => Token::And,
+                        '¬' => Token::Not,
+                        '(' => Token::LParen,
+                        ')' => Token::RParen,
+                        '→' => Token::Implies,
+                        '↔' => Token::Iff,
+                        '⊤' => Token::True,
+                        '⊥' => Token::False,
+
+                        '-' if self.follows(">") => Token::Implies,
+                        '<' if self.follows("->") => Token::Iff,
+
+                        '!' | '~' | '-' if self.symbolic() => Token::Not,
+                        '∧' | '*' if self.symbolic() => Token::And,
+                        '∨' | '+' if self.symbolic() => Token::Or,
+                        '⊕' if self.symbolic() => Token::Xor,
+                        '\'' if self.symbolic() => Token::Prime,
+
+                        ch if ch.is_ascii_alphanumeric() => {
+                            let mut string = String::from(ch);
+
+                            while let Some ((_, ch)) = self.chars.next_if(|(_, ch)| ch.is_ascii_alphanumeric()) {
+                                string.push(ch);
+                            }
+
+                            match self.constant(&string).or_else(|| self.keyword(&string)) {
+                                Some (token) => token,
+                                None => Token::Var(string),
                             }
                         }
+
+                        _ => {
+                            let span = Span::new(start, start + ch.len_utf8());
+
+                            break Some(Err(LexerError::UnexpectedCharacter(ch, span)));
+                        }
+                    };
+
+                    // `&&` and `||` are read as a single `&` and `|`.
+                    if self.symbolic() && ch == '&' {
+                        self.follows("&");
+                    }
+
+                    if self.symbolic() && ch == '|' {
+                        self.follows("|");
                     }
 
-                    _ => Some(Err(LexerError::UnexpectedCharacter(ch))),
+                    break Some(Ok((token, Span::new(start, self.offset()))));
                 },
 
                 None => break None,
@@ -77,7 +213,7 @@ mod tests {
         
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::Var("abc".to_string())))
+            Some(Ok((Token::Var("abc".to_string()), Span::new(0, 3))))
         );
     }
 
@@ -87,7 +223,7 @@ mod tests {
         
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::And))
+            Some(Ok((Token::And, Span::new(0, 1))))
         );
     }
 
@@ -97,7 +233,7 @@ mod tests {
         
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::Or))
+            Some(Ok((Token::Or, Span::new(0, 1))))
         );
     }
 
@@ -107,7 +243,7 @@ mod tests {
         
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::Not))
+            Some(Ok((Token::Not, Span::new(0, 2))))
         );
     }
 
@@ -117,7 +253,7 @@ mod tests {
         
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::LParen))
+            Some(Ok((Token::LParen, Span::new(0, 1))))
         );
     }
 
@@ -127,17 +263,132 @@ mod tests {
         
         assert_eq!(
             lexer.next(),
-            Some(Ok(Token::RParen))
+            Some(Ok((Token::RParen, Span::new(0, 1))))
         );
     }
 
+    #[test]
+    fn should_tokenize_implies() {
+        for string in ["->", "→"] {
+            let mut lexer = Lexer::new(string);
+
+            assert_eq!(
+                lexer.next(),
+                Some(Ok((Token::Implies, Span::new(0, string.len()))))
+            );
+        }
+    }
+
+    #[test]
+    fn should_tokenize_iff() {
+        for string in ["<->", "↔"] {
+            let mut lexer = Lexer::new(string);
+
+            assert_eq!(
+                lexer.next(),
+                Some(Ok((Token::Iff, Span::new(0, string.len()))))
+            );
+        }
+    }
+
+    #[test]
+    fn should_tokenize_incomplete_arrow_as_invalid() {
+        let mut lexer = Lexer::new("<-b");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Err(LexerError::UnexpectedCharacter('<', Span::new(0, 1))))
+        );
+    }
+
+    #[test]
+    fn should_span_tokens_after_multibyte_characters() {
+        let lexer = Lexer::new("¬ab & c");
+
+        let spans: Vec<Span> = lexer.map(|result| result.unwrap().1).collect();
+
+        assert_eq!(spans, vec![Span::new(0, 2), Span::new(2, 4), Span::new(5, 6), Span::new(7, 8)]);
+    }
+
+    #[test]
+    fn should_tokenize_symbolic_aliases() {
+        let tokens: Vec<Token> = Lexer::new("! ~ - && || ∧ ∨ ⊕ * + '")
+            .map(|result| result.unwrap().0)
+            .collect();
+
+        assert_eq!(tokens, vec![
+            Token::Not, Token::Not, Token::Not,
+            Token::And, Token::Or,
+            Token::And, Token::Or, Token::Xor,
+            Token::And, Token::Or,
+            Token::Prime,
+        ]);
+    }
+
+    #[test]
+    fn should_span_doubled_operators() {
+        let mut lexer = Lexer::new("&&");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Ok((Token::And, Span::new(0, 2))))
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn should_tokenize_keywords_only_in_keywords_dialect() {
+        let tokens: Vec<Token> = Lexer::with_dialect("a AND not b xor c or d", Dialect::Keywords)
+            .map(|result| result.unwrap().0)
+            .collect();
+
+        assert_eq!(tokens, vec![
+            Token::Var("a".to_string()), Token::And, Token::Not, Token::Var("b".to_string()),
+            Token::Xor, Token::Var("c".to_string()), Token::Or, Token::Var("d".to_string()),
+        ]);
+
+        let mut lexer = Lexer::new("and");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Ok((Token::Var("and".to_string()), Span::new(0, 3))))
+        );
+    }
+
+    #[test]
+    fn should_tokenize_constants() {
+        let tokens: Vec<Token> = Lexer::new("true FALSE 1 0 ⊤ ⊥ 10 x1")
+            .map(|result| result.unwrap().0)
+            .collect();
+
+        assert_eq!(tokens, vec![
+            Token::True, Token::False, Token::True, Token::False, Token::True, Token::False,
+            Token::Var("10".to_string()), Token::Var("x1".to_string()),
+        ]);
+
+        let tokens: Vec<Token> = Lexer::with_dialect("true 1 ⊤ ⊥", Dialect::Strict)
+            .map(|result| result.unwrap().0)
+            .collect();
+
+        assert_eq!(tokens, vec![
+            Token::Var("true".to_string()), Token::Var("1".to_string()), Token::True, Token::False,
+        ]);
+    }
+
     #[test]
     fn should_tokenize_invalid_char() {
-        let mut lexer = Lexer::new("!");
+        let mut lexer = Lexer::with_dialect("!", Dialect::Strict);
         
         assert_eq!(
             lexer.next(),
-            Some(Err(LexerError::UnexpectedCharacter('!')))
+            Some(Err(LexerError::UnexpectedCharacter('!', Span::new(0, 1))))
+        );
+
+        let mut lexer = Lexer::new("$");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Err(LexerError::UnexpectedCharacter('$', Span::new(0, 1))))
         );
     }
 }
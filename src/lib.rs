@@ -0,0 +1,69 @@
+//! Parsing, simplification and satisfiability of propositional formulas.
+//!
+//! The usual flow is to [`parse`] an infix formula such as `(a & b) | ¬c`
+//! into an [`Expression`], optionally [`Expression::simplify`] it, and then
+//! ask a solver such as [`DynamicSatisfability`] which [`Requirement`]s make
+//! it true or false. For anything beyond small formulas, [`CdclSatisfability`]
+//! finds a single satisfying [`Model`] through a clause-learning solver over
+//! the [`cnf`] encoding of the expression, and [`DpllSatisfability`] is a
+//! plain DPLL search kept as a readable reference to cross-check it against.
+//! A [`BddManager`] gives canonical [`Bdd`]s instead, for equivalence
+//! checks and counting on formulas of moderate size, and [`QuineMcCluskey`]
+//! rewrites such formulas as a minimum sum of products, while [`Espresso`]
+//! finds a small one for larger formulas.
+//!
+//! ```
+//! use satisfability_problem::{parse, DynamicSatisfability, Requirement};
+//!
+//! let expression = parse("a & ¬b").unwrap();
+//!
+//! let requirement = DynamicSatisfability::new(&expression).satisfies(true);
+//!
+//! assert_eq!(
+//!     requirement,
+//!     Requirement::All(
+//!         Box::new(Requirement::Var("a".to_string(), true)),
+//!         Box::new(Requirement::Var("b".to_string(), false)),
+//!     )
+//! );
+//! ```
+
+pub mod bdd;
+pub mod cnf;
+pub mod diagnostic;
+pub mod expression;
+pub mod lexer;
+pub mod minimization;
+pub mod natural;
+pub mod parser;
+pub mod satisfability;
+pub mod truth_table;
+
+#[cfg(test)]
+mod testing;
+
+pub use bdd::{Bdd, BddManager};
+pub use expression::{EvaluationError, Expression};
+pub use diagnostic::Diagnostic;
+pub use lexer::{Dialect, Lexer, LexerError, Span, Token};
+pub use minimization::{Cover, Cube, Espresso, Minimization, MinimizationError, QuineMcCluskey};
+pub use parser::{Parser, ParserError, ParserResult};
+pub use natural::Natural;
+pub use truth_table::TruthTable;
+pub use satisfability::{
+    AllSatisfability, CdclSatisfability, CdclSolver, Correction, CorrectionSets, Corrections, DpllSatisfability, DpllSolver, DynamicSatisfability,
+    Entailment, Equivalence, Expectative, Extraction, GeneralSatisfability, IncrementalSatisfability, MaxSatError, MaxSatSolver, MaxSatisfability, Model,
+    ModelCounter, Models, Optimum, Requirement, UnsatisfiableCore,
+};
+
+/// Parses an infix formula into an [`Expression`].
+///
+/// This is a shorthand for building a [`Lexer`] and feeding it to a [`Parser`].
+pub fn parse (string: &str) -> ParserResult {
+    Parser::new(Lexer::new(string)).parse()
+}
+
+/// Like [`parse`], accepting the operator spellings of `dialect`.
+pub fn parse_with (string: &str, dialect: Dialect) -> ParserResult {
+    Parser::new(Lexer::with_dialect(string, dialect)).parse()
+}
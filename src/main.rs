@@ -1,37 +1,289 @@
-mod expression;
-mod parser;
-mod lexer;
-mod satisfability;
 mod cli;
 
-use cli::CLI;
+use std::fs;
+use std::io::{self, Write};
+use std::process;
 
-use lexer::Lexer;
-use parser::Parser;
-use satisfability::DynamicSatisfability;
+use cli::{CLI, Command, Dialect, Encoding, Extraction, Order, TableFormat};
+
+use satisfability_problem::{parse_with, Diagnostic, Expression, DynamicSatisfability, CdclSatisfability, Equivalence, CdclSolver, AllSatisfability, ModelCounter, UnsatisfiableCore, CorrectionSets, MaxSatSolver, Model, QuineMcCluskey, Espresso};
+use satisfability_problem::satisfability;
+use satisfability_problem::bdd::{self, BddManager, Reordering};
+use satisfability_problem::cnf::{self, dimacs, Cnf};
+use satisfability_problem::lexer;
+use satisfability_problem::truth_table::{self, TruthTable, TruthTableError};
 
 fn main () {  
     let arguments = CLI::arguments();
 
-    let lexer = Lexer::new(arguments.string());
+    if let Some (command) = arguments.command() {
+        match command {
+            Command::Equiv { left, right } => print_equivalence(left, right, arguments.dialect()),
+            Command::Core { constraints, extraction } => print_core(constraints, arguments.dialect(), *extraction),
+            Command::Repair { constraints, limit } => print_repairs(constraints, arguments.dialect(), *limit),
+        }
+    } else if arguments.dimacs() {
+        solve_dimacs(arguments.string());
+    } else if arguments.wcnf() {
+        solve_wcnf(arguments.string());
+    } else if let Some (format) = arguments.truth_table() {
+        print_truth_table(arguments.string(), arguments.dialect(), format, arguments.max_variables());
+    } else if arguments.all() {
+        print_all(arguments.string(), arguments.dialect(), arguments.project(), arguments.limit(), arguments.cubes());
+    } else if arguments.count() {
+        print_count(arguments.string(), arguments.dialect(), arguments.project());
+    } else if arguments.bdd() {
+        print_bdd(arguments.string(), arguments.dialect(), arguments.order());
+    } else if arguments.minimize() {
+        print_minimum(arguments.string(), arguments.dialect());
+    } else if arguments.espresso() {
+        print_espresso(arguments.string(), arguments.dialect(), arguments.dont_care());
+    } else if arguments.export_dimacs() {
+        export_dimacs(arguments.string(), arguments.dialect(), arguments.encoding());
+    } else {
+        solve_formula(arguments.string(), arguments.dialect(), arguments.requirements());
+    }
+}
 
-    let mut parser = Parser::new(lexer);
+fn parse (string: &str, dialect: Dialect) -> Expression {
+    let dialect = match dialect {
+        Dialect::Strict => lexer::Dialect::Strict,
+        Dialect::Symbolic => lexer::Dialect::Symbolic,
+        Dialect::Keywords => lexer::Dialect::Keywords,
+    };
 
-    let expression = match parser.parse() {
+    match parse_with(string, dialect) {
         Ok (expression) => expression,
         Err (error) => {
-            panic!("{:?}", error);
+            eprint!("{}", Diagnostic::from_source(&error, string).render(string));
+            process::exit(1);
+        }
+    }
+}
+
+fn export_dimacs (string: &str, dialect: Dialect, encoding: Encoding) {
+    let expression = parse(string, dialect);
+
+    let cnf = match encoding {
+        Encoding::Tseitin => Cnf::from_expression_with(&expression, cnf::Encoding::Tseitin),
+        Encoding::PlaistedGreenbaum => Cnf::from_expression_with(&expression, cnf::Encoding::PlaistedGreenbaum),
+        Encoding::Direct => Cnf::from_expression_direct(&expression),
+    };
+
+    print!("{}", dimacs::write(&cnf));
+}
+
+fn print_truth_table (string: &str, dialect: Dialect, format: TableFormat, limit: usize) {
+    let expression = parse(string, dialect);
+
+    let format = match format {
+        TableFormat::Text => truth_table::Format::Text,
+        TableFormat::Markdown => truth_table::Format::Markdown,
+        TableFormat::Csv => truth_table::Format::Csv,
+        TableFormat::Latex => truth_table::Format::Latex,
+    };
+
+    match TruthTable::with_limit(&expression, limit) {
+        Ok (table) => {
+            let mut output = io::BufWriter::new(io::stdout().lock());
+
+            // Stop quietly once the reader goes away, as with `| head`.
+            if table.write(format, &mut output).and_then(|()| output.flush()).is_err() {
+                process::exit(1);
+            }
+        }
+
+        Err (TruthTableError::TooManyVariables (count, limit)) => {
+            eprintln!("error: the formula has {} variables, more than the limit of {} (see --max-variables)", count, limit);
+            process::exit(1);
         }
+    }
+}
+
+fn print_all (string: &str, dialect: Dialect, project: Option<&[String]>, limit: Option<usize>, cubes: bool) {
+    let expression = parse(string, dialect);
+
+    let names: Vec<String> = match project {
+        Some (names) => names.to_vec(),
+        None => expression.variables().into_iter().collect(),
     };
 
+    let mut satisfability = AllSatisfability::new(&expression)
+        .project(names.iter().cloned())
+        .cubes(cubes);
+
+    if let Some (limit) = limit {
+        satisfability = satisfability.limit(limit);
+    }
+
+    let mut found = false;
+
+    for model in satisfability.models() {
+        let values: Vec<String> = names.iter()
+            .map(|name| match model.get(name) {
+                Some (true) => format!("{}=1", name),
+                Some (false) => format!("{}=0", name),
+                None => format!("{}=-", name),
+            })
+            .collect();
+
+        println!("{}", values.join(" "));
+
+        found = true;
+    }
+
+    if !found {
+        println!("unsatisfiable");
+    }
+}
+
+fn print_count (string: &str, dialect: Dialect, project: Option<&[String]>) {
+    let expression = parse(string, dialect);
+
+    let counter = match project {
+        Some (names) => ModelCounter::new(&expression).project(names.iter().cloned()),
+        None => ModelCounter::new(&expression),
+    };
+
+    println!("{}", counter.count());
+}
+
+fn print_equivalence (left: &str, right: &str, dialect: Dialect) {
+    let left = parse(left, dialect);
+    let right = parse(right, dialect);
+
+    match Equivalence::new(&left, &right).counterexample() {
+        None => println!("equivalent"),
+        Some (model) => {
+            println!("not equivalent");
+            println!("{}", format_model(&model));
+            println!("left: {}", left.evaluate(&model).unwrap());
+            println!("right: {}", right.evaluate(&model).unwrap());
+        }
+    }
+}
+
+fn parse_constraints (strings: &[String], dialect: Dialect) -> Vec<(String, Expression)> {
+    strings.iter()
+        .map(|string| (string.clone(), parse(string, dialect)))
+        .collect()
+}
+
+fn format_model (model: &Model) -> String {
+    let values: Vec<String> = model.iter()
+        .map(|(name, &value)| format!("{}={}", name, value as u8))
+        .collect();
+
+    values.join(" ")
+}
+
+fn print_core (strings: &[String], dialect: Dialect, extraction: Extraction) {
+    let constraints = parse_constraints(strings, dialect);
+
+    let extraction = match extraction {
+        Extraction::Deletion => satisfability::Extraction::Deletion,
+        Extraction::QuickXplain => satisfability::Extraction::QuickXplain,
+    };
+
+    match UnsatisfiableCore::new(&constraints).extraction(extraction).minimal() {
+        Some (core) => {
+            for name in core {
+                println!("{}", name);
+            }
+        }
+
+        None => println!("satisfiable"),
+    }
+}
+
+fn print_repairs (strings: &[String], dialect: Dialect, limit: Option<usize>) {
+    let constraints = parse_constraints(strings, dialect);
+
+    let corrections = CorrectionSets::new(&constraints).corrections();
+
+    for correction in corrections.take(limit.unwrap_or(usize::MAX)) {
+        if correction.names.is_empty() {
+            println!("satisfiable");
+        } else {
+            println!("drop: {}", correction.names.join(", "));
+        }
+
+        println!("{}", format_model(&correction.model));
+    }
+}
+
+fn print_bdd (string: &str, dialect: Dialect, order: Order) {
+    let expression = parse(string, dialect);
+
+    let mut manager = BddManager::new();
+
+    let root = manager.from_expression(&expression);
+
+    let initial = manager.order().join(" ");
+
+    let reordering = match order {
+        Order::Appearance => reorder(&mut manager, root, &[]),
+        Order::Dfs => reorder(&mut manager, root, &bdd::dfs_order(&expression)),
+        Order::Force => reorder(&mut manager, root, &bdd::force_order(&expression)),
+        Order::Sift => manager.sift(&[root]),
+    };
+
+    println!("before: {} nodes ({})", reordering.before, initial);
+    println!("after: {} nodes ({})", reordering.after, manager.order().join(" "));
+}
+
+fn reorder (manager: &mut BddManager, root: bdd::Bdd, order: &[String]) -> Reordering {
+    let before = manager.size(&[root]);
+
+    manager.reorder(order);
+
+    Reordering { before, after: manager.size(&[root]) }
+}
+
+fn print_minimum (string: &str, dialect: Dialect) {
+    let expression = parse(string, dialect);
+
+    match QuineMcCluskey::new(&expression).minimize() {
+        Ok (cover) => println!("{}", cover),
+        Err (error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn print_espresso (string: &str, dialect: Dialect, dont_care: Option<&str>) {
+    let expression = parse(string, dialect);
+    let dont_care = dont_care.map(|string| parse(string, dialect));
+
+    let mut espresso = Espresso::new(&expression);
+
+    if let Some (dont_care) = dont_care.as_ref() {
+        espresso = espresso.dont_care(dont_care);
+    }
+
+    let minimization = match espresso.minimize() {
+        Ok (minimization) => minimization,
+        Err (error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+
+    println!("{}", minimization.cover);
+    println!("before: {} literals", minimization.before);
+    println!("after: {} literals", minimization.after);
+}
+
+fn solve_formula (string: &str, dialect: Dialect, requirements: bool) {
+    let expression = parse(string, dialect);
+
     println!("Expression:");
     println!();
     println!("{:#?}", expression);
     println!();
 
-    let expression = expression
-        .de_morgan()
-        .optimize();
+    let expression = expression.simplify();
 
     println!();
 
@@ -42,19 +294,110 @@ fn main () {
 
     println!();
 
+    println!("Satisfying assignment:");
+    println!();
+
+    match CdclSatisfability::new(&expression).satisfies(true) {
+        Some (model) => {
+            for (name, value) in model {
+                println!("{} -> {}", name, value);
+            }
+        }
+
+        None => println!("unsatisfiable"),
+    }
+
+    println!();
+
+    // Distributing the requirements may take exponential time, so they
+    // are only printed on request.
+    if !requirements {
+        return;
+    }
+
     let satisfability = DynamicSatisfability::new(&expression);
 
-    for expectative in [true] {
-        let requirements = satisfability.satisfies(expectative);
-    
-        println!();
-        println!("Requirements to be {}:", expectative);
-        println!();
+    let requirements = satisfability.satisfies(true);
 
-        println!("{:#?}", requirements);
+    println!();
+    println!("Requirements to be true:");
+    println!();
+
+    println!("{:#?}", requirements);
+
+    println!("{}", requirements);
+}
 
-        requirements.format();
+fn solve_dimacs (path: &str) {
+    let cnf = fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|input| dimacs::parse(&input).map_err(|error| error.to_string()));
 
-        println!();
+    let cnf = match cnf {
+        Ok (cnf) => cnf,
+        Err (error) => {
+            eprintln!("{}: {}", path, error);
+            process::exit(1);
+        }
+    };
+
+    match CdclSolver::from_cnf(&cnf).solve() {
+        Some (values) => {
+            println!("s SATISFIABLE");
+            print_values(&cnf, &values);
+        }
+
+        None => println!("s UNSATISFIABLE"),
     }
 }
+
+fn solve_wcnf (path: &str) {
+    let wcnf = fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|input| dimacs::parse_wcnf(&input).map_err(|error| error.to_string()));
+
+    let wcnf = match wcnf {
+        Ok (wcnf) => wcnf,
+        Err (error) => {
+            eprintln!("{}: {}", path, error);
+            process::exit(1);
+        }
+    };
+
+    match MaxSatSolver::from_wcnf(&wcnf).solve() {
+        Ok (Some ((values, cost))) => {
+            println!("o {}", cost);
+            println!("s OPTIMUM FOUND");
+            print_values(&wcnf.hard, &values);
+        }
+
+        Ok (None) => println!("s UNSATISFIABLE"),
+
+        Err (error) => {
+            eprintln!("{}: {}", path, error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints a `v` line of signed DIMACS literals, in the order of their
+/// numbers in the file.
+fn print_values (cnf: &Cnf, values: &[bool]) {
+    let mut numbers: Vec<(usize, bool)> = cnf.names()
+        .filter_map(|(variable, name)| Some((dimacs::variable_number(name)?, values[variable])))
+        .collect();
+
+    numbers.sort_unstable();
+
+    let literals: Vec<String> = numbers.into_iter()
+        .map(|(number, value)| {
+            if value {
+                format!("{}", number)
+            } else {
+                format!("-{}", number)
+            }
+        })
+        .collect();
+
+    println!("v {} 0", literals.join(" "));
+}
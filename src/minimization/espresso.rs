@@ -0,0 +1,403 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use crate::expression::Expression;
+
+use super::{Cover, Cube, MinimizationError, sum_of_products};
+
+/// Largest number of cubes [`Espresso::minimize`] starts from. Every step
+/// takes time quadratic in the number of cubes, and distributing a
+/// conjunction of `n` disjunctions may give `2^n` cubes that cannot be
+/// merged, as `(a & c) | (a & d) | (b & c) | (b & d)` for `(a | b) & (c | d)`.
+pub const CUBE_LIMIT: usize = 256;
+
+/// A cover found by [`Espresso::minimize`], with the number of literals of
+/// the expression, counted as its occurrences of variables, and of the cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Minimization {
+    pub cover: Cover,
+    pub before: usize,
+    pub after: usize,
+}
+
+/// Heuristic two-level minimisation in the style of Espresso, for formulas
+/// with too many variables for [`super::QuineMcCluskey`].
+///
+/// The expression is turned into a sum of products without enumerating
+/// its assignments, by distributing conjunctions over disjunctions, which
+/// fails beyond [`CUBE_LIMIT`] cubes. That cover is then expanded into
+/// prime implicants and stripped of redundant cubes, and reducing,
+/// expanding and stripping it again is repeated while it gets cheaper.
+/// Every step only asks whether a cube is covered by the original cover
+/// and the don't-care set, which is decided by splitting the cover rather
+/// than by computing where it is false. The result has no redundant cube
+/// nor literal, but may not be a minimum one.
+pub struct Espresso<'a> {
+    expression: &'a Expression,
+    dont_care: Option<&'a Expression>,
+}
+
+impl<'a> Espresso<'a> {
+    pub fn new (expression: &'a Expression) -> Espresso<'a> {
+        Espresso {
+            expression,
+            dont_care: None,
+        }
+    }
+
+    /// Lets the cover take any value where `dont_care` is true.
+    pub fn dont_care (mut self, dont_care: &'a Expression) -> Espresso<'a> {
+        self.dont_care = Some(dont_care);
+        self
+    }
+
+    /// A sum of products agreeing with the expression wherever the
+    /// don't-care expression is false, over the variables of both sorted
+    /// by name.
+    pub fn minimize (&self) -> Result<Minimization, MinimizationError> {
+        let mut variables: BTreeSet<String> = self.expression.variables();
+
+        if let Some (dont_care) = self.dont_care {
+            variables.extend(dont_care.variables());
+        }
+
+        let variables: Vec<String> = variables.into_iter().collect();
+
+        let cubes = |expression| sum_of_products(expression, true, &variables, CUBE_LIMIT).ok_or(MinimizationError::TooManyCubes(CUBE_LIMIT));
+
+        let on = cubes(self.expression)?;
+
+        let dont_care = match self.dont_care {
+            Some (expression) => cubes(expression)?,
+            None => Vec::new(),
+        };
+
+        // The cubes may grow over anything the expression or the
+        // don't-care set makes true.
+        let allowed: Vec<Cube> = on.iter().chain(dont_care.iter()).cloned().collect();
+
+        let mut cubes = irredundant(expand(on, &allowed), &dont_care);
+
+        loop {
+            let candidate = irredundant(expand(reduce(cubes.clone(), &dont_care), &allowed), &dont_care);
+
+            if cost(&candidate) >= cost(&cubes) {
+                break;
+            }
+
+            cubes = candidate;
+        }
+
+        cubes.sort();
+
+        let cover = Cover::new(variables, cubes);
+
+        Ok(Minimization {
+            before: literals(self.expression),
+            after: cover.literals(),
+            cover,
+        })
+    }
+}
+
+/// Number of occurrences of variables in the expression.
+fn literals (expression: &Expression) -> usize {
+    match expression {
+        Expression::Var (_) => 1,
+        Expression::Not (inner) => literals(inner),
+
+        Expression::And (left, right) | Expression::Or (left, right) | Expression::Xor (left, right) |
+        Expression::Implies (left, right) | Expression::Iff (left, right) => literals(left) + literals(right),
+
+        Expression::True | Expression::False => 0,
+    }
+}
+
+/// Number of cubes, then of literals.
+fn cost (cubes: &[Cube]) -> (usize, usize) {
+    (cubes.len(), cubes.iter().map(Cube::literals).sum())
+}
+
+/// Grows every cube, largest first, into a prime implicant by freeing its
+/// variables one at a time while the allowed cubes still cover it, and
+/// drops the cubes the grown ones include.
+fn expand (mut cubes: Vec<Cube>, allowed: &[Cube]) -> Vec<Cube> {
+    cubes.sort_by_key(Cube::literals);
+
+    let mut expanded: Vec<Cube> = Vec::new();
+
+    for cube in cubes.iter() {
+        if expanded.iter().any(|other| other.includes(cube)) {
+            continue;
+        }
+
+        // Freeing first the variables where most other cubes differ lets
+        // the cube grow towards them.
+        let mut order: Vec<usize> = (0..cube.values.len())
+            .filter(|&index| cube.values[index].is_some())
+            .collect();
+
+        order.sort_by_key(|&index| Reverse(cubes.iter().filter(|other| other.values[index] != cube.values[index]).count()));
+
+        let mut cube = cube.clone();
+
+        for index in order {
+            let value = cube.values[index].take();
+
+            if !tautology(&cofactor(allowed, &cube)) {
+                cube.values[index] = value;
+            }
+        }
+
+        expanded.retain(|other| !cube.includes(other));
+        expanded.push(cube);
+    }
+
+    expanded
+}
+
+/// Drops the cubes, smallest first, that the other cubes and the
+/// don't-care set cover.
+fn irredundant (mut cubes: Vec<Cube>, dont_care: &[Cube]) -> Vec<Cube> {
+    cubes.sort_by_key(|cube| Reverse(cube.literals()));
+
+    let mut index = 0;
+
+    while index < cubes.len() {
+        let rest = others(&cubes, index, dont_care);
+
+        if tautology(&cofactor(&rest, &cubes[index])) {
+            cubes.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+
+    cubes
+}
+
+/// Shrinks every cube, largest first, to the smallest cube including the
+/// assignments no other cube nor the don't-care set covers, so that the
+/// next expansion may grow it in another direction. Cubes left with no
+/// such assignment are dropped.
+///
+/// That smallest cube fixes a free variable to a value exactly when the
+/// rest covers the half of the cube where it takes the other value.
+fn reduce (mut cubes: Vec<Cube>, dont_care: &[Cube]) -> Vec<Cube> {
+    cubes.sort_by_key(Cube::literals);
+
+    let mut index = 0;
+
+    while index < cubes.len() {
+        let rest = others(&cubes, index, dont_care);
+
+        if tautology(&cofactor(&rest, &cubes[index])) {
+            cubes.remove(index);
+
+            continue;
+        }
+
+        let mut reduced = cubes[index].clone();
+
+        for variable in 0..reduced.values.len() {
+            if reduced.values[variable].is_some() {
+                continue;
+            }
+
+            // The rest cannot cover both halves, or it would cover the cube.
+            for value in [false, true] {
+                let mut half = cubes[index].clone();
+
+                half.values[variable] = Some(value);
+
+                if tautology(&cofactor(&rest, &half)) {
+                    reduced.values[variable] = Some(!value);
+                }
+            }
+        }
+
+        cubes[index] = reduced;
+        index += 1;
+    }
+
+    cubes
+}
+
+/// Every cube but the one at `index`, with the don't-care set.
+fn others (cubes: &[Cube], index: usize, dont_care: &[Cube]) -> Vec<Cube> {
+    cubes.iter()
+        .enumerate()
+        .filter(|&(other, _)| other != index)
+        .map(|(_, cube)| cube)
+        .chain(dont_care)
+        .cloned()
+        .collect()
+}
+
+fn literal (count: usize, index: usize, value: bool) -> Cube {
+    let mut cube = Cube::universal(count);
+
+    cube.values[index] = Some(value);
+    cube
+}
+
+/// The cubes meeting `cube`, restricted to it: the variables `cube` fixes
+/// are made free.
+fn cofactor (cubes: &[Cube], cube: &Cube) -> Vec<Cube> {
+    cubes.iter()
+        .filter(|other| !other.disjoint(cube))
+        .map(|other| {
+            Cube::new(
+                other.values.iter()
+                    .zip(cube.values.iter())
+                    .map(|(&other, &value)| if value.is_some() { None } else { other })
+                    .collect()
+            )
+        })
+        .collect()
+}
+
+/// The variable fixed by the most cubes, preferring the variables fixed
+/// to both values, and whether it is, or `None` when no cube fixes any.
+fn splitting_variable (cubes: &[Cube]) -> Option<(usize, bool)> {
+    let count = cubes.first()?.values.len();
+
+    (0..count)
+        .map(|index| {
+            let fixed = |value: bool| cubes.iter().filter(|cube| cube.values[index] == Some(value)).count();
+
+            (index, fixed(false), fixed(true))
+        })
+        .filter(|&(_, negative, positive)| negative + positive > 0)
+        .max_by_key(|&(index, negative, positive)| (negative > 0 && positive > 0, negative + positive, Reverse(index)))
+        .map(|(index, negative, positive)| (index, negative > 0 && positive > 0))
+}
+
+/// Whether the cubes cover every assignment. A cover where every variable
+/// is fixed to a single value, called unate, only does with a universal
+/// cube; any other is split on its most binate variable.
+fn tautology (cubes: &[Cube]) -> bool {
+    if cubes.iter().any(Cube::is_universal) {
+        return true;
+    }
+
+    match splitting_variable(cubes) {
+        Some ((index, true)) => {
+            let count = cubes[0].values.len();
+
+            [false, true].into_iter().all(|value| tautology(&cofactor(cubes, &literal(count, index, value))))
+        }
+
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::minimization::QuineMcCluskey;
+    use crate::parse;
+    use crate::testing::Random;
+
+    #[test]
+    fn should_use_dont_cares () {
+        let expression = parse("a & b & c").unwrap();
+        let dont_care = parse("a & b & ¬c").unwrap();
+
+        let minimization = Espresso::new(&expression).dont_care(&dont_care).minimize().unwrap();
+
+        assert_eq!(minimization.cover.to_string(), "a & b");
+        assert_eq!((minimization.before, minimization.after), (3, 2));
+    }
+
+    #[test]
+    fn should_drop_the_consensus () {
+        let minimization = Espresso::new(&parse("(a & b) | (¬a & c) | (b & c)").unwrap()).minimize().unwrap();
+
+        assert_eq!(minimization.cover.to_string(), "(¬a & c) | (a & b)");
+        assert_eq!((minimization.before, minimization.after), (6, 4));
+    }
+
+    #[test]
+    fn should_count_the_literals_of_the_expression () {
+        let minimization = Espresso::new(&parse("a & (b | c)").unwrap()).minimize().unwrap();
+
+        assert_eq!(minimization.cover.to_string(), "(a & c) | (a & b)");
+        assert_eq!((minimization.before, minimization.after), (3, 4));
+    }
+
+    #[test]
+    fn should_keep_wide_minimal_sums () {
+        let expression = parse(
+            &(0..20)
+                .map(|index| format!("(x{0:02} & y{0:02})", index))
+                .collect::<Vec<String>>()
+                .join(" | ")
+        ).unwrap();
+
+        let minimization = Espresso::new(&expression).minimize().unwrap();
+
+        assert_eq!(minimization.cover.cubes().len(), 20);
+        assert_eq!((minimization.before, minimization.after), (40, 40));
+    }
+
+    #[test]
+    fn should_minimize_beyond_exact_limits () {
+        let expression = parse(
+            &(0..24)
+                .map(|index| format!("(x{0:02} & x{1:02}) | (x{0:02} & ¬x{1:02})", index, index + 1))
+                .collect::<Vec<String>>()
+                .join(" | ")
+        ).unwrap();
+
+        let minimization = Espresso::new(&expression).minimize().unwrap();
+
+        assert_eq!(minimization.cover.cubes().len(), 24);
+        assert_eq!((minimization.before, minimization.after), (96, 24));
+    }
+
+    #[test]
+    fn should_minimize_wide_conjunctions_within_the_cube_limit () {
+        let conjunction = |clause: fn (usize) -> String| {
+            parse(&(0..15).map(clause).collect::<Vec<String>>().join(" & ")).unwrap()
+        };
+
+        // Every clause shares `a`, so the distributed cover stays small.
+        let minimization = Espresso::new(&conjunction(|index| format!("(a | b{:02})", index))).minimize().unwrap();
+
+        assert_eq!(minimization.cover.cubes().len(), 2);
+        assert_eq!((minimization.before, minimization.after), (30, 16));
+
+        // With a variable of its own per clause, the minimum cover has
+        // 2^15 cubes, which are refused instead.
+        let expression = conjunction(|index| format!("(a{0:02} | b{0:02})", index));
+
+        assert_eq!(Espresso::new(&expression).minimize(), Err(MinimizationError::TooManyCubes(CUBE_LIMIT)));
+    }
+
+    #[test]
+    fn covers_should_agree_outside_dont_cares () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0x428A2F98D728AE22);
+
+        for _ in 0..60 {
+            let expression = random.expression(&names, 3);
+            let dont_care = random.expression(&names, 2);
+
+            for minimization in [Espresso::new(&expression).minimize().unwrap(), Espresso::new(&expression).dont_care(&dont_care).minimize().unwrap()] {
+                let care = Expression::not(dont_care.clone());
+
+                let before = Expression::and(expression.clone(), care.clone());
+                let after = Expression::and(minimization.cover.to_expression(), care);
+
+                assert!(before.is_equivalent(&after), "{:?} {:?} {}", expression, dont_care, minimization.cover);
+            }
+
+            let exact = QuineMcCluskey::new(&expression).minimize().unwrap();
+            let heuristic = Espresso::new(&expression).minimize().unwrap();
+
+            assert!(expression.is_equivalent(&heuristic.cover.to_expression()));
+            assert!(heuristic.cover.cubes().len() >= exact.cubes().len());
+        }
+    }
+}
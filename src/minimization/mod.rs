@@ -0,0 +1,273 @@
+//! Two-level minimisation: rewriting a formula as a small disjunction of
+//! conjunctions of literals.
+
+mod quine_mccluskey;
+mod espresso;
+
+use std::fmt;
+
+use crate::expression::Expression;
+
+pub use quine_mccluskey::{QuineMcCluskey, LIMIT};
+pub use espresso::{Espresso, Minimization, CUBE_LIMIT};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MinimizationError {
+    /// The expression has the first number of variables, above the second.
+    TooManyVariables (usize, usize),
+
+    /// Distributing the expression gives more cubes than this limit.
+    TooManyCubes (usize),
+}
+
+impl fmt::Display for MinimizationError {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MinimizationError::TooManyVariables (count, limit) => write!(formatter, "the formula has {} variables, more than the limit of {}", count, limit),
+            MinimizationError::TooManyCubes (limit) => write!(formatter, "the sum of products of the formula has more than {} cubes", limit),
+        }
+    }
+}
+
+impl std::error::Error for MinimizationError {}
+
+/// A conjunction of literals over the variables of a [`Cover`]: the value
+/// each variable must take, by position, or `None` when it may take either.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cube {
+    values: Vec<Option<bool>>,
+}
+
+impl Cube {
+    pub fn new (values: Vec<Option<bool>>) -> Cube {
+        Cube {
+            values,
+        }
+    }
+
+    pub fn values (&self) -> &[Option<bool>] {
+        &self.values
+    }
+
+    /// Number of variables the cube fixes.
+    pub fn literals (&self) -> usize {
+        self.values.iter().flatten().count()
+    }
+
+    /// Whether the assignment of the variables, by position, is in the cube.
+    pub fn contains (&self, values: &[bool]) -> bool {
+        self.values.iter()
+            .zip(values)
+            .all(|(cube, value)| cube.is_none_or(|cube| cube == *value))
+    }
+
+    /// The cube with every variable free, which is always true.
+    fn universal (count: usize) -> Cube {
+        Cube::new(vec![None; count])
+    }
+
+    fn is_universal (&self) -> bool {
+        self.values.iter().all(Option::is_none)
+    }
+
+    /// Whether every assignment of `other` is in the cube.
+    fn includes (&self, other: &Cube) -> bool {
+        self.values.iter()
+            .zip(other.values.iter())
+            .all(|(value, other)| value.is_none() || value == other)
+    }
+
+    fn disjoint (&self, other: &Cube) -> bool {
+        self.values.iter()
+            .zip(other.values.iter())
+            .any(|(value, other)| value.is_some() && other.is_some() && value != other)
+    }
+
+    fn intersect (&self, other: &Cube) -> Option<Cube> {
+        self.values.iter()
+            .zip(other.values.iter())
+            .map(|(&value, &other)| match (value, other) {
+                (Some (value), Some (other)) if value != other => None,
+                (value, other) => Some(value.or(other)),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Cube::new)
+    }
+
+    fn to_expression (&self, variables: &[String]) -> Expression {
+        variables.iter()
+            .zip(self.values.iter())
+            .filter_map(|(name, value)| match value {
+                Some (true) => Some(Expression::var(name.as_str())),
+                Some (false) => Some(Expression::not(Expression::var(name.as_str()))),
+                None => None,
+            })
+            .reduce(Expression::and)
+            .unwrap_or(Expression::True)
+    }
+}
+
+/// A sum of products: the disjunction of its cubes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cover {
+    variables: Vec<String>,
+    cubes: Vec<Cube>,
+}
+
+impl Cover {
+    pub fn new (variables: Vec<String>, cubes: Vec<Cube>) -> Cover {
+        Cover {
+            variables,
+            cubes,
+        }
+    }
+
+    /// A sum of products equivalent to the expression, over its variables
+    /// sorted by name, obtained by distributing conjunctions over
+    /// disjunctions. It may be exponentially larger than the expression.
+    pub fn from_expression (expression: &Expression) -> Cover {
+        let variables: Vec<String> = expression.variables().into_iter().collect();
+        let cubes = sum_of_products(expression, true, &variables, usize::MAX).expect("any number of cubes should be allowed");
+
+        Cover::new(variables, cubes)
+    }
+
+    pub fn variables (&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn cubes (&self) -> &[Cube] {
+        &self.cubes
+    }
+
+    /// Number of literals over every cube, the usual measure of the size
+    /// of a two-level circuit.
+    pub fn literals (&self) -> usize {
+        self.cubes.iter().map(Cube::literals).sum()
+    }
+
+    pub fn to_expression (&self) -> Expression {
+        self.cubes.iter()
+            .map(|cube| cube.to_expression(&self.variables))
+            .reduce(Expression::or)
+            .unwrap_or(Expression::False)
+    }
+}
+
+/// Writes the cover as a formula the parser reads back, such as
+/// `(a & ¬b) | c`.
+impl fmt::Display for Cover {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.cubes.is_empty() {
+            return formatter.write_str("false");
+        }
+
+        let cubes: Vec<String> = self.cubes.iter()
+            .map(|cube| {
+                let literals: Vec<String> = self.variables.iter()
+                    .zip(cube.values())
+                    .filter_map(|(name, value)| match value {
+                        Some (true) => Some(name.clone()),
+                        Some (false) => Some(format!("¬{}", name)),
+                        None => None,
+                    })
+                    .collect();
+
+                match literals.len() {
+                    0 => "true".to_string(),
+                    1 => literals.concat(),
+                    _ if self.cubes.len() == 1 => literals.join(" & "),
+                    _ => format!("({})", literals.join(" & ")),
+                }
+            })
+            .collect();
+
+        formatter.write_str(&cubes.join(" | "))
+    }
+}
+
+/// The cubes of a sum of products over `variables` that is true exactly
+/// when the expression takes `value`, or `None` as soon as a part of the
+/// expression needs more than `limit` cubes.
+fn sum_of_products (expression: &Expression, value: bool, variables: &[String], limit: usize) -> Option<Vec<Cube>> {
+    let either = |left: &Expression, right: &Expression, left_value: bool, right_value: bool| {
+        let mut cubes = sum_of_products(left, left_value, variables, limit)?;
+
+        cubes.extend(sum_of_products(right, right_value, variables, limit)?);
+
+        absorb(cubes, limit)
+    };
+
+    let both = |left: &Expression, right: &Expression, left_value: bool, right_value: bool| {
+        product(&sum_of_products(left, left_value, variables, limit)?, &sum_of_products(right, right_value, variables, limit)?, limit)
+    };
+
+    match (expression, value) {
+        (Expression::Var (name), value) => {
+            let mut cube = Cube::universal(variables.len());
+            let index = variables.binary_search(name).expect("the variables should include every name of the expression");
+
+            cube.values[index] = Some(value);
+
+            Some(vec![cube])
+        }
+
+        (Expression::Not (inner), value) => sum_of_products(inner, !value, variables, limit),
+
+        (Expression::And (left, right), true) | (Expression::Or (left, right), false) => both(left, right, value, value),
+        (Expression::And (left, right), false) | (Expression::Or (left, right), true) => either(left, right, value, value),
+
+        (Expression::Implies (left, right), true) => either(left, right, false, true),
+        (Expression::Implies (left, right), false) => both(left, right, true, false),
+
+        (Expression::Xor (left, right), value) | (Expression::Iff (left, right), value) => {
+            // Xor is true, and Iff false, when the operands differ.
+            let differ = matches!(expression, Expression::Xor (..)) == value;
+
+            let mut cubes = both(left, right, true, !differ)?;
+
+            cubes.extend(both(left, right, false, differ)?);
+
+            absorb(cubes, limit)
+        }
+
+        (Expression::True, value) | (Expression::False, value) => {
+            if matches!(expression, Expression::True) == value {
+                Some(vec![Cube::universal(variables.len())])
+            } else {
+                Some(Vec::new())
+            }
+        }
+    }
+}
+
+/// The cubes of the conjunction of two sums of products, or `None` when
+/// there are more than `limit` of them.
+fn product (left: &[Cube], right: &[Cube], limit: usize) -> Option<Vec<Cube>> {
+    let cubes = left.iter()
+        .flat_map(|left| right.iter().filter_map(|right| left.intersect(right)))
+        .collect();
+
+    absorb(cubes, limit)
+}
+
+/// Drops the cubes included in another one, keeping one of equal cubes,
+/// or gives up with `None` once more than `limit` cubes are kept.
+fn absorb (mut cubes: Vec<Cube>, limit: usize) -> Option<Vec<Cube>> {
+    cubes.sort_by_key(Cube::literals);
+
+    let mut kept: Vec<Cube> = Vec::new();
+
+    for cube in cubes {
+        if !kept.iter().any(|other| other.includes(&cube)) {
+            // Cubes kept are never dropped, so there can only be more.
+            if kept.len() == limit {
+                return None;
+            }
+
+            kept.push(cube);
+        }
+    }
+
+    Some(kept)
+}
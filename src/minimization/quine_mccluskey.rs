@@ -0,0 +1,367 @@
+use std::collections::BTreeSet;
+
+use crate::expression::Expression;
+use crate::truth_table::{TruthTable, TruthTableError};
+
+use super::{Cover, Cube, MinimizationError};
+
+/// Largest number of variables [`QuineMcCluskey::minimize`] accepts: the
+/// prime implicants of `n` variables may number close to `3^n / n`.
+pub const LIMIT: usize = 12;
+
+/// An implicant over the bits of a minterm, the first variable being the
+/// most significant one: the bits in `free` may take either value, the
+/// others take their value in `value`, whose free bits are all zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Implicant {
+    free: u32,
+    value: u32,
+}
+
+impl Implicant {
+    fn covers (self, minterm: u32) -> bool {
+        minterm & !self.free == self.value
+    }
+
+    fn literals (self, count: usize) -> usize {
+        count - self.free.count_ones() as usize
+    }
+
+    fn to_cube (self, count: usize) -> Cube {
+        Cube::new(
+            (0..count)
+                .map(|index| {
+                    let bit = 1 << (count - 1 - index);
+
+                    (self.free & bit == 0).then_some(self.value & bit != 0)
+                })
+                .collect()
+        )
+    }
+}
+
+/// Exact two-level minimisation: finds every prime implicant of the
+/// expression by merging minterms that differ in one variable, then
+/// selects a cheapest subset of them covering every minterm, with the
+/// fewest cubes first and the fewest literals second.
+pub struct QuineMcCluskey<'a> {
+    expression: &'a Expression,
+}
+
+impl<'a> QuineMcCluskey<'a> {
+    pub fn new (expression: &'a Expression) -> QuineMcCluskey<'a> {
+        QuineMcCluskey {
+            expression,
+        }
+    }
+
+    /// A minimum sum of products equivalent to the expression, over its
+    /// variables sorted by name.
+    pub fn minimize (&self) -> Result<Cover, MinimizationError> {
+        let table = TruthTable::with_limit(self.expression, LIMIT).map_err(|error| match error {
+            TruthTableError::TooManyVariables (count, limit) => MinimizationError::TooManyVariables(count, limit),
+        })?;
+
+        let count = table.variables().len();
+
+        let minterms: Vec<u32> = table.rows()
+            .enumerate()
+            .filter(|(_, (_, value))| *value)
+            .map(|(minterm, _)| minterm as u32)
+            .collect();
+
+        let mut primes = primes(&minterms, count);
+
+        // Trying the implicants with fewer literals, which cover more
+        // minterms, first finds cheap covers early and prunes the search.
+        primes.sort_by_key(|prime| (prime.literals(count), *prime));
+
+        let mut selection = Selection {
+            covering: minterms.iter()
+                .map(|&minterm| {
+                    (0..primes.len()).filter(|&index| primes[index].covers(minterm)).collect()
+                })
+                .collect(),
+            primes: &primes,
+            minterms: &minterms,
+            count,
+            best: None,
+        };
+
+        selection.select(Vec::new(), (0..minterms.len()).collect());
+
+        let (_, chosen) = selection.best.expect("the prime implicants should cover every minterm");
+
+        let mut cubes: Vec<Cube> = chosen.into_iter()
+            .map(|index| primes[index].to_cube(count))
+            .collect();
+
+        cubes.sort();
+
+        Ok(Cover::new(table.variables().to_vec(), cubes))
+    }
+}
+
+/// The implicants of the minterms that no other implicant contains.
+fn primes (minterms: &[u32], count: usize) -> Vec<Implicant> {
+    let mut primes = Vec::new();
+
+    let mut current: BTreeSet<Implicant> = minterms.iter()
+        .map(|&value| Implicant { free: 0, value })
+        .collect();
+
+    while !current.is_empty() {
+        let mut next = BTreeSet::new();
+        let mut merged = BTreeSet::new();
+
+        for &implicant in current.iter() {
+            for bit in (0..count).map(|index| 1 << index) {
+                if (implicant.free | implicant.value) & bit != 0 {
+                    continue;
+                }
+
+                let partner = Implicant { free: implicant.free, value: implicant.value | bit };
+
+                if current.contains(&partner) {
+                    next.insert(Implicant { free: implicant.free | bit, value: implicant.value });
+
+                    merged.insert(implicant);
+                    merged.insert(partner);
+                }
+            }
+        }
+
+        primes.extend(current.difference(&merged).copied());
+        current = next;
+    }
+
+    primes
+}
+
+/// Branch and bound over the prime implicants: branches on the uncovered
+/// minterm with the fewest implicants left to cover it, so that essential
+/// implicants are taken without branching.
+struct Selection<'a> {
+    primes: &'a [Implicant],
+    minterms: &'a [u32],
+    count: usize,
+
+    /// The implicants covering each minterm.
+    covering: Vec<Vec<usize>>,
+
+    /// The cheapest selection so far, with its cubes and literals.
+    best: Option<((usize, usize), Vec<usize>)>,
+}
+
+impl<'a> Selection<'a> {
+    fn select (&mut self, chosen: Vec<usize>, uncovered: Vec<usize>) {
+        let literals = chosen.iter().map(|&index| self.primes[index].literals(self.count)).sum();
+        let bound = (chosen.len() + self.independent(&uncovered), literals);
+
+        if self.best.as_ref().is_some_and(|(best, _)| bound >= *best) {
+            return;
+        }
+
+        let Some (&minterm) = uncovered.iter().min_by_key(|&&minterm| self.covering[minterm].len()) else {
+            self.best = Some((bound, chosen));
+
+            return;
+        };
+
+        for &prime in self.covering[minterm].clone().iter() {
+            let rest = uncovered.iter()
+                .copied()
+                .filter(|&other| !self.primes[prime].covers(self.minterms[other]))
+                .collect();
+
+            let mut next = chosen.clone();
+
+            next.push(prime);
+
+            self.select(next, rest);
+        }
+    }
+
+    /// Size of a set of uncovered minterms no implicant covers two of,
+    /// each needing an implicant of its own.
+    fn independent (&self, uncovered: &[usize]) -> usize {
+        let mut used = vec![false; self.primes.len()];
+        let mut count = 0;
+
+        for &minterm in uncovered.iter() {
+            if self.covering[minterm].iter().all(|&prime| !used[prime]) {
+                for &prime in self.covering[minterm].iter() {
+                    used[prime] = true;
+                }
+
+                count += 1;
+            }
+        }
+
+        count
+    }
+}
+
+impl Expression {
+    /// A minimum sum-of-products form of the expression, through
+    /// [`QuineMcCluskey`].
+    pub fn minimize (&self) -> Result<Expression, MinimizationError> {
+        QuineMcCluskey::new(self).minimize().map(|cover| cover.to_expression())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::testing::Random;
+
+    fn minimize (string: &str) -> Cover {
+        QuineMcCluskey::new(&parse(string).unwrap()).minimize().unwrap()
+    }
+
+    #[test]
+    fn should_minimize_to_common_scenarios () {
+        let scenarios = [
+            // Distributive -> Complement -> Identity
+            ("a & (¬a | b)", "a & b"),
+
+            // Absorption
+            ("a | (a & b)", "a"),
+
+            // Distributive -> Complement -> Identity, on disjunctions
+            ("a | (¬a & b)", "a | b"),
+
+            // De Morgan -> Absorption
+            ("¬(¬a & ¬b) & (a | b | c)", "a | b"),
+
+            // Idempotence
+            ("(a & b) | (b & a)", "a & b"),
+        ];
+
+        for (before, after) in scenarios {
+            assert_eq!(parse(before).unwrap().minimize(), Ok(parse(after).unwrap()), "{}", before);
+        }
+    }
+
+    #[test]
+    fn should_drop_the_consensus () {
+        let cover = minimize("(a & b) | (¬a & c) | (b & c)");
+
+        assert_eq!(cover.to_string(), "(¬a & c) | (a & b)");
+        assert_eq!(cover.literals(), 4);
+    }
+
+    #[test]
+    fn should_choose_among_cyclic_covers () {
+        // Six primes cover these minterms in a cycle, and two covers of
+        // three cubes tie.
+        let cover = minimize("(¬a & b) | (a & ¬b) | (b & ¬c) | (¬b & c)");
+
+        assert_eq!(cover.cubes().len(), 3);
+        assert_eq!(cover.literals(), 6);
+    }
+
+    #[test]
+    fn parity_should_keep_every_minterm () {
+        let cover = minimize("a ^ b ^ c");
+
+        assert_eq!(cover.cubes().len(), 4);
+        assert_eq!(cover.literals(), 12);
+    }
+
+    #[test]
+    fn constants_should_have_trivial_covers () {
+        assert_eq!(minimize("a & ¬a").to_string(), "false");
+        assert_eq!(minimize("a | ¬a").to_string(), "true");
+        assert_eq!(minimize("a | ¬a").cubes(), &[Cube::new(vec![None])]);
+    }
+
+    #[test]
+    fn should_refuse_too_many_variables () {
+        let names: Vec<String> = (0..=LIMIT).map(|index| format!("x{}", index)).collect();
+        let expression = parse(&names.join(" | ")).unwrap();
+
+        assert_eq!(QuineMcCluskey::new(&expression).minimize(), Err(MinimizationError::TooManyVariables(LIMIT + 1, LIMIT)));
+    }
+
+    #[test]
+    fn covers_should_be_equivalent_and_prime () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0x9B05688C2B3E6C1F);
+
+        for _ in 0..60 {
+            let expression = random.expression(&names, 3);
+            let cover = QuineMcCluskey::new(&expression).minimize().unwrap();
+
+            assert!(expression.is_equivalent(&cover.to_expression()), "{:?} {}", expression, cover);
+
+            let table = TruthTable::new(&expression).unwrap();
+
+            for cube in cover.cubes() {
+                for (index, value) in cube.values().iter().enumerate() {
+                    if value.is_none() {
+                        continue;
+                    }
+
+                    let mut values = cube.values().to_vec();
+
+                    values[index] = None;
+
+                    let larger = Cube::new(values);
+
+                    assert!(
+                        table.rows().any(|(values, value)| larger.contains(&values) && !value),
+                        "{:?} is not prime in {}", cube, cover,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn covers_should_be_minimum () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0x6A09E667F3BCC908);
+
+        for _ in 0..80 {
+            let used = 3 + random.below(2);
+            let expression = random.expression(&names[..used], 3);
+            let table = TruthTable::new(&expression).unwrap();
+            let count = table.variables().len();
+
+            let minterms: Vec<u32> = table.rows()
+                .enumerate()
+                .filter(|(_, (_, value))| *value)
+                .map(|(minterm, _)| minterm as u32)
+                .collect();
+
+            let primes = primes(&minterms, count);
+
+            assert!(primes.len() <= 16, "{:?}", expression);
+
+            // The cheapest subset of the primes covering every minterm.
+            let minimum = (0..1u32 << primes.len())
+                .filter(|subset| {
+                    minterms.iter().all(|&minterm| {
+                        (0..primes.len()).any(|index| subset & (1 << index) != 0 && primes[index].covers(minterm))
+                    })
+                })
+                .map(|subset| {
+                    let chosen: Vec<&Implicant> = primes.iter()
+                        .enumerate()
+                        .filter(|(index, _)| subset & (1 << index) != 0)
+                        .map(|(_, prime)| prime)
+                        .collect();
+
+                    (chosen.len(), chosen.iter().map(|prime| prime.literals(count)).sum::<usize>())
+                })
+                .min()
+                .unwrap();
+
+            let cover = QuineMcCluskey::new(&expression).minimize().unwrap();
+
+            assert_eq!((cover.cubes().len(), cover.literals()), minimum, "{:?} {}", expression, cover);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Arbitrary-precision natural numbers, for counts that overflow `u64`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Shl};
+
+/// An unbounded non-negative integer stored as little-endian 32-bit limbs,
+/// without leading zero limbs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Natural {
+    limbs: Vec<u32>,
+}
+
+impl Natural {
+    pub fn zero () -> Natural {
+        Natural::default()
+    }
+
+    pub fn one () -> Natural {
+        Natural::from(1u64)
+    }
+
+    /// `2^exponent`.
+    pub fn power_of_two (exponent: usize) -> Natural {
+        Natural::one() << exponent
+    }
+
+    pub fn is_zero (&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The value, if it fits in a `u64`.
+    pub fn to_u64 (&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0] as u64),
+            2 => Some(self.limbs[0] as u64 | (self.limbs[1] as u64) << 32),
+            _ => None,
+        }
+    }
+
+    fn normalize (mut self) -> Natural {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+
+        self
+    }
+
+    /// Divides in place by a small divisor, returning the remainder.
+    fn divide (&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+
+        for limb in self.limbs.iter_mut().rev() {
+            let current = remainder << 32 | *limb as u64;
+
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+
+        remainder as u32
+    }
+}
+
+impl From<u64> for Natural {
+    fn from (value: u64) -> Natural {
+        Natural {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        }.normalize()
+    }
+}
+
+impl Ord for Natural {
+    fn cmp (&self, other: &Natural) -> Ordering {
+        self.limbs.len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for Natural {
+    fn partial_cmp (&self, other: &Natural) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AddAssign<&Natural> for Natural {
+    fn add_assign (&mut self, other: &Natural) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0u64;
+
+        for (index, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(index).copied().unwrap_or(0) as u64 + carry;
+
+            *limb = sum as u32;
+            carry = sum >> 32;
+
+            if carry == 0 && index >= other.limbs.len() {
+                break;
+            }
+        }
+
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add for Natural {
+    type Output = Natural;
+
+    fn add (mut self, other: Natural) -> Natural {
+        self += &other;
+        self
+    }
+}
+
+impl Mul<&Natural> for &Natural {
+    type Output = Natural;
+
+    fn mul (self, other: &Natural) -> Natural {
+        if self.is_zero() || other.is_zero() {
+            return Natural::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+
+        for (i, &left) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+
+            for (j, &right) in other.limbs.iter().enumerate() {
+                let product = left as u64 * right as u64 + limbs[i + j] as u64 + carry;
+
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        Natural { limbs }.normalize()
+    }
+}
+
+impl MulAssign<&Natural> for Natural {
+    fn mul_assign (&mut self, other: &Natural) {
+        *self = &*self * other;
+    }
+}
+
+impl Shl<usize> for Natural {
+    type Output = Natural;
+
+    fn shl (self, shift: usize) -> Natural {
+        if self.is_zero() {
+            return self;
+        }
+
+        let (words, bits) = (shift / 32, shift % 32);
+
+        let mut limbs = vec![0u32; words];
+        let mut carry = 0u32;
+
+        for limb in self.limbs {
+            if bits == 0 {
+                limbs.push(limb);
+            } else {
+                limbs.push(limb << bits | carry);
+                carry = limb >> (32 - bits);
+            }
+        }
+
+        limbs.push(carry);
+
+        Natural { limbs }.normalize()
+    }
+}
+
+impl fmt::Display for Natural {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return formatter.pad("0");
+        }
+
+        // Peel off nine decimal digits at a time.
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+
+        while !value.is_zero() {
+            chunks.push(value.divide(1_000_000_000));
+        }
+
+        let mut output = chunks.pop().unwrap().to_string();
+
+        for chunk in chunks.iter().rev() {
+            output.push_str(&format!("{:09}", chunk));
+        }
+
+        formatter.pad(&output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_add_with_carry () {
+        let sum = Natural::from(u64::MAX) + Natural::one();
+
+        assert_eq!(sum, Natural::power_of_two(64));
+        assert_eq!(sum.to_u64(), None);
+        assert_eq!(sum.to_string(), "18446744073709551616");
+    }
+
+    #[test]
+    fn should_multiply_and_print () {
+        let mut power = Natural::one();
+
+        for _ in 0..100 {
+            power *= &Natural::from(3u64);
+        }
+
+        assert_eq!(power.to_string(), "515377520732011331036461129765621272702107522001");
+        assert_eq!((&power * &Natural::zero()), Natural::zero());
+    }
+
+    #[test]
+    fn should_compare_by_magnitude () {
+        assert!(Natural::power_of_two(40) > Natural::from(u32::MAX as u64));
+        assert!(Natural::from(7u64) < Natural::from(9u64));
+        assert_eq!(Natural::zero().to_string(), "0");
+        assert_eq!((Natural::from(5u64) << 33).to_u64(), Some(5 << 33));
+    }
+}
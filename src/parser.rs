@@ -1,5 +1,5 @@
 use crate::expression::Expression;
-use crate::lexer::{Token, Lexer, LexerError};
+use crate::lexer::{Token, Span, Lexer, LexerError};
 
 use std::iter::Peekable;
 
@@ -7,42 +7,82 @@ use std::iter::Peekable;
 pub enum ParserError {
     Lexer (LexerError),
 
-    ExpectedToken (Token),
-    UnexpectedToken(Token),
+    /// A `)` was expected at the second span to close the `(` at the first.
+    UnclosedParen (Span, Span),
+    UnexpectedToken (Token, Span),
 
-    ExpectedEndOfInput,
-    UnexpectedEndOfInput,
+    ExpectedEndOfInput (Token, Span),
+    UnexpectedEndOfInput (Span),
 }
 
 pub type ParserResult = Result<Expression, ParserError>;
 
 pub struct Parser<'a> {
     tokens: Peekable<Lexer<'a>>,
+    end: Span,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(lexer: Lexer<'a>) -> Parser<'a> {
+        let length = lexer.source().len();
+
         Parser {
             tokens: lexer.peekable(),
+            end: Span::new(length, length),
         }
     }
 
     pub fn parse(&mut self) -> ParserResult {
-        let expression = self.parse_or()?;
+        let expression = self.parse_iff()?;
 
         match self.tokens.next() {
             None => Ok(expression),
-            Some (Ok(token)) => Err(ParserError::UnexpectedToken(token)),
+            Some (Ok((token, span))) => Err(ParserError::ExpectedEndOfInput(token, span)),
             Some (Err(error)) => Err(ParserError::Lexer(error)),
         }
     }
 
+    fn parse_iff(&mut self) -> ParserResult {
+        let mut left = self.parse_implies()?;
+
+        loop {
+            match self.tokens.peek() {
+                Some(Ok((Token::Iff, _))) => {
+                    self.tokens.next();
+
+                    let right = self.parse_implies()?;
+
+                    left = Expression::Iff(Box::new(left), Box::new(right));
+                }
+
+                _ => break Ok(left),
+            }
+        }
+    }
+
+    /// Implication is right-associative: `a -> b -> c` is `a -> (b -> c)`.
+    fn parse_implies(&mut self) -> ParserResult {
+        let left = self.parse_or()?;
+
+        match self.tokens.peek() {
+            Some(Ok((Token::Implies, _))) => {
+                self.tokens.next();
+
+                let right = self.parse_implies()?;
+
+                Ok(Expression::Implies(Box::new(left), Box::new(right)))
+            }
+
+            _ => Ok(left),
+        }
+    }
+
     fn parse_or(&mut self) -> ParserResult {
         let mut left = self.parse_and()?;
 
         loop {
             match self.tokens.peek() {
-                Some(Ok(Token::Or)) => {
+                Some(Ok((Token::Or, _))) => {
                     self.tokens.next();
 
                     let right = self.parse_and()?;
@@ -60,7 +100,7 @@ impl<'a> Parser<'a> {
 
         loop {
             match self.tokens.peek() {
-                Some(Ok(Token::And)) => {
+                Some(Ok((Token::And, _))) => {
                     self.tokens.next();
 
                     let right = self.parse_xor()?;
@@ -78,7 +118,7 @@ impl<'a> Parser<'a> {
 
         loop {
             match self.tokens.peek() {
-                Some(Ok(Token::Xor)) => {
+                Some(Ok((Token::Xor, _))) => {
                     self.tokens.next();
 
                     let right = self.parse_atom()?;
@@ -91,28 +131,44 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_atom(&mut self) -> ParserResult {
+        let mut expr = self.parse_primary()?;
+
+        while let Some(Ok((Token::Prime, _))) = self.tokens.peek() {
+            self.tokens.next();
+
+            expr = Expression::Not(Box::new(expr));
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_primary(&mut self) -> ParserResult {
         match self.tokens.next() {
-            Some(Ok(Token::Var(name))) => Ok(Expression::Var(name)),
+            Some(Ok((Token::Var(name), _))) => Ok(Expression::Var(name)),
+            Some(Ok((Token::True, _))) => Ok(Expression::True),
+            Some(Ok((Token::False, _))) => Ok(Expression::False),
 
-            Some(Ok(Token::Not)) => {
+            Some(Ok((Token::Not, _))) => {
                 let expr = self.parse_atom()?;
 
                 Ok(Expression::Not(Box::new(expr)))
             }
 
-            Some(Ok(Token::LParen)) => {
-                let expr = self.parse_or()?;
+            Some(Ok((Token::LParen, opened))) => {
+                let expr = self.parse_iff()?;
 
                 match self.tokens.next() {
-                    Some(Ok(Token::RParen)) => Ok(expr),
-                    _ => Err(ParserError::ExpectedToken(Token::RParen)),
+                    Some(Ok((Token::RParen, _))) => Ok(expr),
+                    Some(Ok((_, span))) => Err(ParserError::UnclosedParen(opened, span)),
+                    Some(Err(error)) => Err(ParserError::Lexer(error)),
+                    None => Err(ParserError::UnclosedParen(opened, self.end)),
                 }
             }
 
-            Some(Ok(token)) => Err(ParserError::UnexpectedToken(token)),
+            Some(Ok((token, span))) => Err(ParserError::UnexpectedToken(token, span)),
             Some(Err(error)) => Err(ParserError::Lexer(error)),
 
-            None => Err(ParserError::UnexpectedEndOfInput),
+            None => Err(ParserError::UnexpectedEndOfInput(self.end)),
         }
     }
 }
@@ -187,4 +243,142 @@ mod tests {
             )
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_report_unclosed_paren() {
+        let lexer = Lexer::new("(a & b");
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(parser.parse(), Err(ParserError::UnclosedParen(Span::new(0, 1), Span::new(6, 6))));
+    }
+
+    #[test]
+    fn should_report_unexpected_token() {
+        let lexer = Lexer::new("a & )");
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(parser.parse(), Err(ParserError::UnexpectedToken(Token::RParen, Span::new(4, 5))));
+    }
+
+    #[test]
+    fn parse_implies_right_associative() {
+        let lexer = Lexer::new("a -> b → c");
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse(),
+
+            Ok(
+                Expression::implies(
+                    Expression::var("a"),
+                    Expression::implies(
+                        Expression::var("b"),
+                        Expression::var("c"),
+                    ),
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn parse_implies_and_iff_below_or() {
+        let lexer = Lexer::new("a | b -> c <-> (d -> e)");
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse(),
+
+            Ok(
+                Expression::iff(
+                    Expression::implies(
+                        Expression::Or(
+                            Box::new(Expression::var("a")),
+                            Box::new(Expression::var("b")),
+                        ),
+                        Expression::var("c"),
+                    ),
+                    Expression::implies(
+                        Expression::var("d"),
+                        Expression::var("e"),
+                    ),
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn parse_boolean_algebra_style() {
+        let lexer = Lexer::new("a * b' + (c + d)'");
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse(),
+
+            Ok(
+                Expression::Or(
+                    Box::new(Expression::And(
+                        Box::new(Expression::var("a")),
+                        Box::new(Expression::Not(Box::new(Expression::var("b")))),
+                    )),
+                    Box::new(Expression::Not(Box::new(Expression::Or(
+                        Box::new(Expression::var("c")),
+                        Box::new(Expression::var("d")),
+                    )))),
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn parse_programming_style() {
+        let lexer = Lexer::new("!a && (b || -c) -> ~d");
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse(),
+
+            Ok(
+                Expression::implies(
+                    Expression::And(
+                        Box::new(Expression::Not(Box::new(Expression::var("a")))),
+                        Box::new(Expression::Or(
+                            Box::new(Expression::var("b")),
+                            Box::new(Expression::Not(Box::new(Expression::var("c")))),
+                        )),
+                    ),
+                    Expression::Not(Box::new(Expression::var("d"))),
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn parse_and_simplify_constants() {
+        let lexer = Lexer::new("a & (b | true)");
+        let mut parser = Parser::new(lexer);
+
+        let expression = parser.parse().unwrap();
+
+        assert_eq!(
+            expression,
+
+            Expression::And(
+                Box::new(Expression::var("a")),
+                Box::new(Expression::Or(
+                    Box::new(Expression::var("b")),
+                    Box::new(Expression::True),
+                )),
+            )
+        );
+
+        assert_eq!(expression.simplify(), Expression::var("a"));
+    }
+
+    #[test]
+    fn parse_unicode_constants() {
+        let lexer = Lexer::new("⊥ | ¬⊤ | 0");
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(parser.parse().unwrap().simplify(), Expression::False);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::cnf::{Cnf, Literal};
+use crate::expression::Expression;
+
+use super::{CdclSolver, Model};
+
+/// Enumerates the assignments satisfying an expression with the CDCL
+/// solver, blocking every solution once it has been reported.
+pub struct AllSatisfability<'a> {
+    expression: &'a Expression,
+    projection: Option<Vec<String>>,
+    limit: Option<usize>,
+    cubes: bool,
+}
+
+impl<'a> AllSatisfability<'a> {
+    pub fn new (expression: &'a Expression) -> AllSatisfability<'a> {
+        AllSatisfability {
+            expression,
+            projection: None,
+            limit: None,
+            cubes: false,
+        }
+    }
+
+    /// Only reports the given variables, so assignments differing
+    /// elsewhere are reported once. Names the expression does not use are
+    /// free and appear with both values.
+    pub fn project<I, S> (mut self, names: I) -> AllSatisfability<'a>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.projection = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Stops after `limit` solutions.
+    pub fn limit (mut self, limit: usize) -> AllSatisfability<'a> {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Reports cubes instead of full assignments: a variable missing from
+    /// a solution may take either value. Cubes never overlap.
+    pub fn cubes (mut self, cubes: bool) -> AllSatisfability<'a> {
+        self.cubes = cubes;
+        self
+    }
+
+    /// Streams the solutions, searching for the next one on demand.
+    pub fn models (&self) -> Models<'a> {
+        let mut cnf = Cnf::new();
+
+        let literal = cnf.encode(self.expression);
+
+        cnf.add_clause(vec![literal]);
+
+        let names: Vec<String> = match &self.projection {
+            Some (names) => names.clone(),
+            None => self.expression.variables().into_iter().collect(),
+        };
+
+        let projection = names.into_iter()
+            .map(|name| (cnf.variable(&name), name))
+            .collect();
+
+        Models {
+            expression: self.expression,
+            solver: CdclSolver::from_cnf(&cnf),
+            cnf,
+            projection,
+            remaining: self.limit,
+            cubes: self.cubes.then(Vec::new),
+        }
+    }
+}
+
+/// The solutions of an [`AllSatisfability`], in no particular order.
+pub struct Models<'a> {
+    expression: &'a Expression,
+    cnf: Cnf,
+    solver: CdclSolver,
+    projection: Vec<(usize, String)>,
+    remaining: Option<usize>,
+
+    /// The cubes reported so far, when reporting cubes.
+    cubes: Option<Vec<Model>>,
+}
+
+impl<'a> Models<'a> {
+    /// Drops projected variables from `model` while the expression stays
+    /// true whatever their value, and the cube stays disjoint from the
+    /// ones already reported.
+    fn generalize (&self, model: Model, values: &[bool]) -> Model {
+        let reported = self.cubes.as_deref().unwrap_or_default();
+
+        // Variables outside of the projection keep the value the solver
+        // found, which extends every assignment of the cube.
+        let mut assignment = self.cnf.model(values);
+
+        for (_, name) in self.projection.iter() {
+            assignment.remove(name);
+        }
+
+        let mut cube = model;
+
+        for (_, name) in self.projection.iter() {
+            let value = cube.remove(name).expect("projected variable should be assigned");
+
+            let mut candidate = assignment.clone();
+
+            candidate.extend(cube.iter().map(|(name, &value)| (name.clone(), value)));
+
+            let implies = self.expression.partial_evaluate(&candidate) == Expression::True;
+            let disjoint = reported.iter().all(|other| conflicts(&cube, other));
+
+            if !(implies && disjoint) {
+                cube.insert(name.clone(), value);
+            }
+        }
+
+        cube
+    }
+}
+
+/// Whether two cubes assign some variable opposite values.
+fn conflicts (left: &Model, right: &Model) -> bool {
+    left.iter().any(|(name, value)| right.get(name).is_some_and(|other| other != value))
+}
+
+impl<'a> Iterator for Models<'a> {
+    type Item = Model;
+
+    fn next (&mut self) -> Option<Model> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+
+        let values = self.solver.solve()?;
+
+        let mut model: Model = self.projection.iter()
+            .map(|(variable, name)| (name.clone(), values[*variable]))
+            .collect();
+
+        if self.cubes.is_some() {
+            model = self.generalize(model, &values);
+        }
+
+        let blocking: Vec<Literal> = self.projection.iter()
+            .filter_map(|(variable, name)| Some(Literal::new(*variable, !*model.get(name)?)))
+            .collect();
+
+        self.solver.add_clause(&blocking);
+
+        if let Some (cubes) = self.cubes.as_mut() {
+            cubes.push(model.clone());
+        }
+
+        if let Some (remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+
+        Some(model)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::testing::{Random, evaluate};
+
+    /// Number of full assignments of `count` variables a cube stands for.
+    fn weight (cube: &Model, count: usize) -> usize {
+        1 << (count - cube.len())
+    }
+
+    #[test]
+    fn should_enumerate_every_model_once () {
+        let expression = parse("a ^ b ^ c").unwrap();
+
+        let mut models: Vec<Model> = AllSatisfability::new(&expression).models().collect();
+
+        models.sort();
+
+        assert_eq!(models.len(), 4);
+        assert!(models.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(models.iter().all(|model| evaluate(&expression, model)));
+    }
+
+    #[test]
+    fn should_stop_at_limit () {
+        let expression = parse("a | b | c").unwrap();
+
+        assert_eq!(AllSatisfability::new(&expression).limit(3).models().count(), 3);
+        assert_eq!(AllSatisfability::new(&expression).limit(0).models().count(), 0);
+    }
+
+    #[test]
+    fn unsatisfiable_should_have_no_models () {
+        let expression = parse("a & ¬a").unwrap();
+
+        assert_eq!(AllSatisfability::new(&expression).models().next(), None);
+    }
+
+    #[test]
+    fn should_project_onto_variables () {
+        let expression = parse("(a | b) & c").unwrap();
+
+        let mut models: Vec<Model> = AllSatisfability::new(&expression)
+            .project(["a", "d"])
+            .models()
+            .collect();
+
+        models.sort();
+
+        assert_eq!(
+            models,
+            vec![
+                Model::from([("a".to_string(), false), ("d".to_string(), false)]),
+                Model::from([("a".to_string(), false), ("d".to_string(), true)]),
+                Model::from([("a".to_string(), true), ("d".to_string(), false)]),
+                Model::from([("a".to_string(), true), ("d".to_string(), true)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn tautology_should_be_a_single_empty_cube () {
+        let expression = parse("a | ¬a").unwrap();
+
+        let cubes: Vec<Model> = AllSatisfability::new(&expression).cubes(true).models().collect();
+
+        assert_eq!(cubes, vec![Model::new()]);
+    }
+
+    #[test]
+    fn cubes_should_partition_the_models () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0xBB67AE8584CAA73B);
+
+        for _ in 0..100 {
+            let expression = random.expression(&names, 5);
+            let variables = expression.variables();
+
+            let models = AllSatisfability::new(&expression).models().count();
+            let cubes: Vec<Model> = AllSatisfability::new(&expression).cubes(true).models().collect();
+
+            assert!(cubes.len() <= models);
+            assert_eq!(cubes.iter().map(|cube| weight(cube, variables.len())).sum::<usize>(), models);
+
+            for cube in cubes.iter() {
+                let residual = expression.partial_evaluate(cube);
+
+                assert_eq!(residual, Expression::True, "{:?} {:?}", expression, cube);
+            }
+        }
+    }
+}
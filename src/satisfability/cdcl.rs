@@ -0,0 +1,1006 @@
+use std::mem;
+
+use crate::cnf::{Cnf, Literal};
+use crate::expression::Expression;
+
+use super::Model;
+
+const VARIABLE_DECAY: f64 = 0.95;
+const CLAUSE_DECAY: f64 = 0.999;
+const RESTART_UNIT: usize = 100;
+
+#[derive(Debug, Clone)]
+struct ClauseData {
+    literals: Vec<Literal>,
+    learnt: bool,
+    activity: f64,
+}
+
+/// Binary max-heap of variables ordered by their VSIDS activity.
+#[derive(Debug, Clone, Default)]
+struct VariableHeap {
+    heap: Vec<usize>,
+    positions: Vec<Option<usize>>,
+}
+
+impl VariableHeap {
+    fn grow (&mut self, variables: usize) {
+        self.positions.resize(variables, None);
+    }
+
+    fn contains (&self, variable: usize) -> bool {
+        self.positions[variable].is_some()
+    }
+
+    fn insert (&mut self, variable: usize, activity: &[f64]) {
+        if self.contains(variable) {
+            return;
+        }
+
+        self.positions[variable] = Some(self.heap.len());
+        self.heap.push(variable);
+        self.up(self.heap.len() - 1, activity);
+    }
+
+    fn increased (&mut self, variable: usize, activity: &[f64]) {
+        if let Some (position) = self.positions[variable] {
+            self.up(position, activity);
+        }
+    }
+
+    fn pop (&mut self, activity: &[f64]) -> Option<usize> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop()?;
+
+        self.positions[top] = None;
+
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last] = Some(0);
+            self.down(0, activity);
+        }
+
+        Some(top)
+    }
+
+    fn up (&mut self, mut position: usize, activity: &[f64]) {
+        let variable = self.heap[position];
+
+        while position > 0 {
+            let parent = (position - 1) / 2;
+
+            if activity[self.heap[parent]] >= activity[variable] {
+                break;
+            }
+
+            self.heap[position] = self.heap[parent];
+            self.positions[self.heap[position]] = Some(position);
+            position = parent;
+        }
+
+        self.heap[position] = variable;
+        self.positions[variable] = Some(position);
+    }
+
+    fn down (&mut self, mut position: usize, activity: &[f64]) {
+        let variable = self.heap[position];
+
+        loop {
+            let left = 2 * position + 1;
+
+            if left >= self.heap.len() {
+                break;
+            }
+
+            let right = left + 1;
+
+            let child = if right < self.heap.len() && activity[self.heap[right]] > activity[self.heap[left]] {
+                right
+            } else {
+                left
+            };
+
+            if activity[self.heap[child]] <= activity[variable] {
+                break;
+            }
+
+            self.heap[position] = self.heap[child];
+            self.positions[self.heap[position]] = Some(position);
+            position = child;
+        }
+
+        self.heap[position] = variable;
+        self.positions[variable] = Some(position);
+    }
+}
+
+/// Result of one restart-bounded run of [`CdclSolver::search`].
+enum Outcome {
+    Satisfiable,
+    Unsatisfiable,
+    /// Unsatisfiable under these assumptions only.
+    Failed (Vec<Literal>),
+    Restart,
+}
+
+/// A conflict-driven clause-learning SAT solver.
+///
+/// Clauses are watched by two literals, conflicts are analysed up to the
+/// first unique implication point, branching follows VSIDS activity with
+/// phase saving, search restarts on the Luby sequence and the learnt
+/// clause database is periodically halved by activity.
+///
+/// The solver is incremental: clauses may be added between searches, and
+/// [`CdclSolver::solve_with_assumptions`] searches under temporary unit
+/// assumptions, keeping the clauses learnt along the way.
+#[derive(Debug, Clone, Default)]
+pub struct CdclSolver {
+    clauses: Vec<ClauseData>,
+    watches: Vec<Vec<usize>>,
+
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    phases: Vec<bool>,
+    seen: Vec<bool>,
+
+    trail: Vec<Literal>,
+    trail_limits: Vec<usize>,
+    head: usize,
+
+    activity: Vec<f64>,
+    variable_increment: f64,
+    clause_increment: f64,
+    heap: VariableHeap,
+
+    learnts: usize,
+    max_learnts: f64,
+    conflicts: usize,
+    inconsistent: bool,
+}
+
+impl CdclSolver {
+    pub fn new () -> CdclSolver {
+        CdclSolver {
+            variable_increment: 1.0,
+            clause_increment: 1.0,
+            ..CdclSolver::default()
+        }
+    }
+
+    pub fn from_cnf (cnf: &Cnf) -> CdclSolver {
+        let mut solver = CdclSolver::new();
+
+        solver.reserve(cnf.variables());
+
+        for clause in cnf.clauses() {
+            solver.add_clause(clause);
+        }
+
+        solver
+    }
+
+    pub fn variables (&self) -> usize {
+        self.values.len()
+    }
+
+    /// Number of conflicts met since the solver was created.
+    pub fn conflicts (&self) -> usize {
+        self.conflicts
+    }
+
+    pub fn add_variable (&mut self) -> usize {
+        let variable = self.variables();
+
+        self.reserve(variable + 1);
+
+        variable
+    }
+
+    /// Makes sure variables `0..variables` exist.
+    pub fn reserve (&mut self, variables: usize) {
+        if variables <= self.variables() {
+            return;
+        }
+
+        let start = self.variables();
+
+        self.values.resize(variables, None);
+        self.levels.resize(variables, 0);
+        self.reasons.resize(variables, None);
+        self.phases.resize(variables, false);
+        self.seen.resize(variables, false);
+        self.activity.resize(variables, 0.0);
+        self.watches.resize(2 * variables, Vec::new());
+        self.heap.grow(variables);
+
+        for variable in start..variables {
+            self.heap.insert(variable, &self.activity);
+        }
+    }
+
+    /// Adds a clause between two searches. Returns `false` once the clause
+    /// database is known to be unsatisfiable.
+    pub fn add_clause (&mut self, clause: &[Literal]) -> bool {
+        self.backtrack(0);
+
+        if self.inconsistent {
+            return false;
+        }
+
+        if let Some (variable) = clause.iter().map(|literal| literal.variable()).max() {
+            self.reserve(variable + 1);
+        }
+
+        let mut literals = clause.to_vec();
+
+        literals.sort();
+        literals.dedup();
+
+        if literals.windows(2).any(|pair| pair[0] == !pair[1]) {
+            return true;
+        }
+
+        if literals.iter().any(|&literal| self.value(literal) == Some(true)) {
+            return true;
+        }
+
+        literals.retain(|&literal| self.value(literal).is_none());
+
+        match literals.len() {
+            0 => {
+                self.inconsistent = true;
+            }
+
+            1 => {
+                self.enqueue(literals[0], None);
+
+                if self.propagate().is_some() {
+                    self.inconsistent = true;
+                }
+            }
+
+            _ => {
+                self.attach(literals, false);
+            }
+        }
+
+        !self.inconsistent
+    }
+
+    /// Searches for an assignment satisfying every clause, indexed by variable.
+    pub fn solve (&mut self) -> Option<Vec<bool>> {
+        self.solve_with_assumptions(&[]).ok()
+    }
+
+    /// Searches for an assignment satisfying every clause in which every
+    /// assumption is true. When there is none, returns the assumptions
+    /// that could not hold together with the clauses, empty when the
+    /// clauses are unsatisfiable by themselves.
+    pub fn solve_with_assumptions (&mut self, assumptions: &[Literal]) -> Result<Vec<bool>, Vec<Literal>> {
+        if self.inconsistent {
+            return Err(Vec::new());
+        }
+
+        if let Some (variable) = assumptions.iter().map(|literal| literal.variable()).max() {
+            self.reserve(variable + 1);
+        }
+
+        self.backtrack(0);
+
+        if self.propagate().is_some() {
+            self.inconsistent = true;
+
+            return Err(Vec::new());
+        }
+
+        if self.max_learnts == 0.0 {
+            self.max_learnts = (self.clauses.len() as f64 / 3.0).max(1000.0);
+        }
+
+        let mut restarts = 0;
+
+        loop {
+            let budget = luby(restarts) * RESTART_UNIT;
+
+            match self.search(budget, assumptions) {
+                Outcome::Satisfiable => {
+                    let model = self.values.iter()
+                        .map(|value| value.unwrap_or(false))
+                        .collect();
+
+                    self.backtrack(0);
+
+                    break Ok(model);
+                }
+
+                Outcome::Unsatisfiable => {
+                    self.inconsistent = true;
+
+                    break Err(Vec::new());
+                }
+
+                Outcome::Failed (failed) => {
+                    self.backtrack(0);
+
+                    break Err(failed);
+                }
+
+                Outcome::Restart => {
+                    restarts += 1;
+                    self.max_learnts *= 1.1;
+                }
+            }
+        }
+    }
+
+    fn search (&mut self, budget: usize, assumptions: &[Literal]) -> Outcome {
+        let mut conflicts = 0;
+
+        loop {
+            match self.propagate() {
+                Some (conflict) => {
+                    self.conflicts += 1;
+                    conflicts += 1;
+
+                    if self.decision_level() == 0 {
+                        return Outcome::Unsatisfiable;
+                    }
+
+                    let (learnt, level) = self.analyze(conflict);
+
+                    self.backtrack(level);
+
+                    if learnt.len() == 1 {
+                        self.enqueue(learnt[0], None);
+                    } else {
+                        let asserting = learnt[0];
+                        let index = self.attach(learnt, true);
+
+                        self.bump_clause(index);
+                        self.enqueue(asserting, Some(index));
+                    }
+
+                    self.variable_increment /= VARIABLE_DECAY;
+                    self.clause_increment /= CLAUSE_DECAY;
+                }
+
+                None => {
+                    if conflicts >= budget {
+                        self.backtrack(0);
+
+                        return Outcome::Restart;
+                    }
+
+                    if self.learnts as f64 >= self.max_learnts + self.trail.len() as f64 {
+                        self.reduce();
+                    }
+
+                    // Every assumption gets a decision level of its own, even
+                    // when it is already true, so that level `i` holds the
+                    // `i`-th assumption.
+                    let next = match assumptions.get(self.decision_level()) {
+                        Some (&assumption) => match self.value(assumption) {
+                            Some (true) => None,
+                            Some (false) => return Outcome::Failed(self.analyze_final(assumption)),
+                            None => Some(assumption),
+                        },
+
+                        None => match self.pick_branch() {
+                            Some (literal) => Some(literal),
+                            None => return Outcome::Satisfiable,
+                        },
+                    };
+
+                    self.trail_limits.push(self.trail.len());
+
+                    if let Some (literal) = next {
+                        self.enqueue(literal, None);
+                    }
+                }
+            }
+        }
+    }
+
+    fn value (&self, literal: Literal) -> Option<bool> {
+        self.values[literal.variable()].map(|value| value == literal.is_positive())
+    }
+
+    fn decision_level (&self) -> usize {
+        self.trail_limits.len()
+    }
+
+    fn enqueue (&mut self, literal: Literal, reason: Option<usize>) {
+        let variable = literal.variable();
+
+        self.values[variable] = Some(literal.is_positive());
+        self.levels[variable] = self.decision_level();
+        self.reasons[variable] = reason;
+        self.trail.push(literal);
+    }
+
+    fn attach (&mut self, literals: Vec<Literal>, learnt: bool) -> usize {
+        let index = self.clauses.len();
+
+        self.watches[literals[0].index()].push(index);
+        self.watches[literals[1].index()].push(index);
+
+        self.clauses.push(ClauseData {
+            literals,
+            learnt,
+            activity: 0.0,
+        });
+
+        if learnt {
+            self.learnts += 1;
+        }
+
+        index
+    }
+
+    /// Propagates every pending assignment, returning a falsified clause if any.
+    fn propagate (&mut self) -> Option<usize> {
+        let mut conflict = None;
+
+        while conflict.is_none() && self.head < self.trail.len() {
+            let falsified = !self.trail[self.head];
+
+            self.head += 1;
+
+            let watchers = mem::take(&mut self.watches[falsified.index()]);
+            let mut kept = Vec::with_capacity(watchers.len());
+
+            for (position, &index) in watchers.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend_from_slice(&watchers[position..]);
+
+                    break;
+                }
+
+                let literals = &mut self.clauses[index].literals;
+
+                if literals[0] == falsified {
+                    literals.swap(0, 1);
+                }
+
+                let first = literals[0];
+
+                if self.values[first.variable()] == Some(first.is_positive()) {
+                    kept.push(index);
+
+                    continue;
+                }
+
+                let replacement = (2..literals.len()).find(|&k| {
+                    let literal = literals[k];
+
+                    self.values[literal.variable()] != Some(!literal.is_positive())
+                });
+
+                match replacement {
+                    Some (k) => {
+                        literals.swap(1, k);
+
+                        let watched = literals[1];
+
+                        self.watches[watched.index()].push(index);
+                    }
+
+                    None => {
+                        kept.push(index);
+
+                        if self.value(first) == Some(false) {
+                            conflict = Some(index);
+                        } else {
+                            self.enqueue(first, Some(index));
+                        }
+                    }
+                }
+            }
+
+            self.watches[falsified.index()] = kept;
+        }
+
+        if conflict.is_some() {
+            self.head = self.trail.len();
+        }
+
+        conflict
+    }
+
+    /// Derives the first-UIP clause of a conflict and the level to jump back to.
+    fn analyze (&mut self, conflict: usize) -> (Vec<Literal>, usize) {
+        let mut learnt = vec![Literal::positive(0)];
+        let mut pending = 0;
+        let mut implied = None;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+
+        loop {
+            if self.clauses[clause].learnt {
+                self.bump_clause(clause);
+            }
+
+            for position in 0..self.clauses[clause].literals.len() {
+                let literal = self.clauses[clause].literals[position];
+                let variable = literal.variable();
+
+                if Some(literal) == implied || self.seen[variable] || self.levels[variable] == 0 {
+                    continue;
+                }
+
+                self.seen[variable] = true;
+                self.bump_variable(variable);
+
+                if self.levels[variable] == self.decision_level() {
+                    pending += 1;
+                } else {
+                    learnt.push(literal);
+                }
+            }
+
+            let literal = loop {
+                index -= 1;
+
+                if self.seen[self.trail[index].variable()] {
+                    break self.trail[index];
+                }
+            };
+
+            self.seen[literal.variable()] = false;
+            implied = Some(literal);
+            pending -= 1;
+
+            if pending == 0 {
+                learnt[0] = !literal;
+
+                break;
+            }
+
+            clause = self.reasons[literal.variable()]
+                .expect("implied literal should have a reason");
+        }
+
+        let candidates = learnt.split_off(1);
+
+        for literal in candidates.iter() {
+            if !self.redundant(*literal) {
+                learnt.push(*literal);
+            }
+        }
+
+        for literal in candidates {
+            self.seen[literal.variable()] = false;
+        }
+
+        let mut level = 0;
+
+        if learnt.len() > 1 {
+            let position = (1..learnt.len())
+                .max_by_key(|&position| self.levels[learnt[position].variable()])
+                .unwrap_or(1);
+
+            learnt.swap(1, position);
+            level = self.levels[learnt[1].variable()];
+        }
+
+        (learnt, level)
+    }
+
+    /// The assumptions that imply `!assumption`, `assumption` included,
+    /// while every decision on the trail is an assumption.
+    fn analyze_final (&mut self, assumption: Literal) -> Vec<Literal> {
+        let mut failed = vec![assumption];
+
+        if self.levels[assumption.variable()] == 0 {
+            return failed;
+        }
+
+        self.seen[assumption.variable()] = true;
+
+        for index in (self.trail_limits[0]..self.trail.len()).rev() {
+            let literal = self.trail[index];
+            let variable = literal.variable();
+
+            if !self.seen[variable] {
+                continue;
+            }
+
+            self.seen[variable] = false;
+
+            match self.reasons[variable] {
+                None => failed.push(literal),
+
+                Some (reason) => {
+                    for &other in self.clauses[reason].literals[1..].iter() {
+                        if self.levels[other.variable()] > 0 {
+                            self.seen[other.variable()] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        failed
+    }
+
+    /// A learnt literal is redundant when its reason only mentions literals
+    /// already in the learnt clause or fixed at the root.
+    fn redundant (&self, literal: Literal) -> bool {
+        match self.reasons[literal.variable()] {
+            None => false,
+            Some (reason) => self.clauses[reason].literals[1..].iter().all(|other| {
+                self.seen[other.variable()] || self.levels[other.variable()] == 0
+            }),
+        }
+    }
+
+    fn backtrack (&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+
+        let limit = self.trail_limits[level];
+
+        for position in limit..self.trail.len() {
+            let literal = self.trail[position];
+            let variable = literal.variable();
+
+            self.values[variable] = None;
+            self.reasons[variable] = None;
+            self.phases[variable] = literal.is_positive();
+            self.heap.insert(variable, &self.activity);
+        }
+
+        self.trail.truncate(limit);
+        self.trail_limits.truncate(level);
+        self.head = limit;
+    }
+
+    fn pick_branch (&mut self) -> Option<Literal> {
+        while let Some (variable) = self.heap.pop(&self.activity) {
+            if self.values[variable].is_none() {
+                return Some(Literal::new(variable, self.phases[variable]));
+            }
+        }
+
+        None
+    }
+
+    fn bump_variable (&mut self, variable: usize) {
+        self.activity[variable] += self.variable_increment;
+
+        if self.activity[variable] > 1e100 {
+            for activity in self.activity.iter_mut() {
+                *activity *= 1e-100;
+            }
+
+            self.variable_increment *= 1e-100;
+        }
+
+        self.heap.increased(variable, &self.activity);
+    }
+
+    fn bump_clause (&mut self, index: usize) {
+        self.clauses[index].activity += self.clause_increment;
+
+        if self.clauses[index].activity > 1e20 {
+            for clause in self.clauses.iter_mut().filter(|clause| clause.learnt) {
+                clause.activity *= 1e-20;
+            }
+
+            self.clause_increment *= 1e-20;
+        }
+    }
+
+    fn locked (&self, index: usize) -> bool {
+        let first = self.clauses[index].literals[0];
+
+        self.reasons[first.variable()] == Some(index) && self.value(first) == Some(true)
+    }
+
+    /// Drops the less active half of the learnt clauses.
+    fn reduce (&mut self) {
+        let mut candidates: Vec<usize> = (0..self.clauses.len())
+            .filter(|&index| {
+                self.clauses[index].learnt
+                    && self.clauses[index].literals.len() > 2
+                    && !self.locked(index)
+            })
+            .collect();
+
+        candidates.sort_by(|&left, &right| {
+            self.clauses[left].activity.total_cmp(&self.clauses[right].activity)
+        });
+
+        let mut removed = vec![false; self.clauses.len()];
+
+        for &index in candidates.iter().take(candidates.len() / 2) {
+            removed[index] = true;
+        }
+
+        let mut renumbered = vec![None; self.clauses.len()];
+        let mut clauses = Vec::with_capacity(self.clauses.len());
+
+        for (index, clause) in mem::take(&mut self.clauses).into_iter().enumerate() {
+            if removed[index] {
+                self.learnts -= 1;
+            } else {
+                renumbered[index] = Some(clauses.len());
+                clauses.push(clause);
+            }
+        }
+
+        self.clauses = clauses;
+
+        for reason in self.reasons.iter_mut() {
+            *reason = reason.and_then(|index| renumbered[index]);
+        }
+
+        for watchers in self.watches.iter_mut() {
+            watchers.clear();
+        }
+
+        for (index, clause) in self.clauses.iter().enumerate() {
+            self.watches[clause.literals[0].index()].push(index);
+            self.watches[clause.literals[1].index()].push(index);
+        }
+    }
+}
+
+/// The Luby restart sequence: 1, 1, 2, 1, 1, 2, 4, 1, ...
+fn luby (mut index: usize) -> usize {
+    let mut size = 1;
+    let mut power = 1;
+
+    while size < index + 1 {
+        size = 2 * size + 1;
+        power *= 2;
+    }
+
+    while size - 1 != index {
+        size = (size - 1) / 2;
+        power /= 2;
+        index %= size;
+    }
+
+    power
+}
+
+pub struct CdclSatisfability<'a> {
+    expression: &'a Expression,
+}
+
+impl<'a> CdclSatisfability<'a> {
+    pub fn new (expression: &'a Expression) -> CdclSatisfability<'a> {
+        CdclSatisfability {
+            expression
+        }
+    }
+
+    /// Finds an assignment making the expression evaluate to `expectative`.
+    pub fn satisfies (&self, expectative: bool) -> Option<Model> {
+        let mut cnf = Cnf::new();
+
+        let literal = cnf.encode(self.expression);
+
+        cnf.add_clause(vec![if expectative { literal } else { !literal }]);
+
+        CdclSolver::from_cnf(&cnf)
+            .solve()
+            .map(|values| cnf.model(&values))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::{Random, evaluate, satisfied, brute_force};
+
+    #[test]
+    fn luby_should_follow_the_sequence () {
+        let sequence: Vec<usize> = (0..15).map(luby).collect();
+
+        assert_eq!(sequence, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn var_expression_should_satisfies_true () {
+        let expression = Expression::var("a");
+
+        let model = CdclSatisfability::new(&expression).satisfies(true);
+
+        assert_eq!(model, Some(Model::from([("a".to_string(), true)])));
+    }
+
+    #[test]
+    fn contradiction_should_not_be_satisfiable () {
+        let expression = Expression::And(
+            Box::new(Expression::var("a")),
+            Box::new(Expression::Not(Box::new(Expression::var("a")))),
+        );
+
+        assert_eq!(CdclSatisfability::new(&expression).satisfies(true), None);
+        assert!(CdclSatisfability::new(&expression).satisfies(false).is_some());
+    }
+
+    #[test]
+    fn xor_expression_should_satisfies_false () {
+        let expression = Expression::Xor(
+            Box::new(Expression::var("a")),
+            Box::new(Expression::Not(Box::new(Expression::var("b")))),
+        );
+
+        let model = CdclSatisfability::new(&expression).satisfies(false).unwrap();
+
+        assert!(!evaluate(&expression, &model));
+    }
+
+    #[test]
+    fn should_refute_pigeonhole () {
+        let pigeons = 6;
+        let holes = 5;
+        let variable = |pigeon: usize, hole: usize| pigeon * holes + hole;
+
+        let mut solver = CdclSolver::new();
+
+        for pigeon in 0..pigeons {
+            let clause: Vec<Literal> = (0..holes).map(|hole| Literal::positive(variable(pigeon, hole))).collect();
+
+            solver.add_clause(&clause);
+        }
+
+        for hole in 0..holes {
+            for first in 0..pigeons {
+                for second in first + 1..pigeons {
+                    solver.add_clause(&[
+                        Literal::negative(variable(first, hole)),
+                        Literal::negative(variable(second, hole)),
+                    ]);
+                }
+            }
+        }
+
+        assert_eq!(solver.solve(), None);
+    }
+
+    #[test]
+    fn should_agree_with_brute_force_on_random_formulas () {
+        let mut random = Random::new(0x2545F4914F6CDD1D);
+
+        for _ in 0..300 {
+            let clauses = random.clauses(8, 36, 3);
+
+            let mut solver = CdclSolver::new();
+
+            solver.reserve(8);
+
+            for clause in clauses.iter() {
+                solver.add_clause(clause);
+            }
+
+            match solver.solve() {
+                Some (model) => assert!(satisfied(&clauses, &model)),
+                None => assert!(!brute_force(&clauses, 8)),
+            }
+        }
+    }
+
+    #[test]
+    fn should_solve_formulas_with_thousands_of_variables () {
+        let mut random = Random::new(0x9E3779B97F4A7C15);
+
+        let clauses = random.clauses(3000, 9000, 3);
+
+        let mut solver = CdclSolver::new();
+
+        for clause in clauses.iter() {
+            solver.add_clause(clause);
+        }
+
+        let model = solver.solve().expect("under-constrained formula should be satisfiable");
+
+        assert!(satisfied(&clauses, &model));
+    }
+
+    #[test]
+    fn should_satisfy_long_xor_chains () {
+        let expression = (1..300)
+            .map(|index| Expression::var(format!("x{}", index)))
+            .fold(Expression::var("x0"), |left, right| {
+                Expression::Xor(Box::new(left), Box::new(right))
+            });
+
+        let model = CdclSatisfability::new(&expression).satisfies(true).unwrap();
+
+        assert!(evaluate(&expression, &model));
+    }
+
+    #[test]
+    fn should_accept_clauses_between_searches () {
+        let mut solver = CdclSolver::new();
+
+        solver.add_clause(&[Literal::positive(0), Literal::positive(1)]);
+
+        assert!(solver.solve().is_some());
+
+        solver.add_clause(&[Literal::negative(0)]);
+        solver.add_clause(&[Literal::negative(1)]);
+
+        assert_eq!(solver.solve(), None);
+    }
+
+    #[test]
+    fn should_solve_under_assumptions () {
+        let mut solver = CdclSolver::new();
+
+        solver.add_clause(&[Literal::negative(0), Literal::positive(1)]);
+        solver.add_clause(&[Literal::negative(1), Literal::negative(2)]);
+
+        let values = solver.solve_with_assumptions(&[Literal::positive(0)]).unwrap();
+
+        assert!(values[1] && !values[2]);
+
+        let failed = solver.solve_with_assumptions(&[Literal::positive(3), Literal::positive(0), Literal::positive(2)]).unwrap_err();
+
+        assert_eq!(failed.len(), 2);
+        assert!(failed.contains(&Literal::positive(0)) && failed.contains(&Literal::positive(2)));
+
+        // Failing under assumptions leaves the clauses satisfiable.
+        assert!(solver.solve_with_assumptions(&[Literal::positive(2)]).is_ok());
+        assert!(solver.solve().is_some());
+    }
+
+    #[test]
+    fn contradictory_assumptions_should_fail_together () {
+        let mut solver = CdclSolver::new();
+
+        solver.add_clause(&[Literal::positive(0), Literal::positive(1)]);
+
+        let failed = solver.solve_with_assumptions(&[Literal::positive(1), Literal::negative(0), Literal::negative(1)]).unwrap_err();
+
+        assert!(failed.contains(&Literal::positive(1)) && failed.contains(&Literal::negative(1)));
+
+        solver.add_clause(&[Literal::negative(0)]);
+        solver.add_clause(&[Literal::negative(1)]);
+
+        assert_eq!(solver.solve_with_assumptions(&[Literal::positive(2)]), Err(Vec::new()));
+    }
+
+    #[test]
+    fn failed_assumptions_should_be_unsatisfiable () {
+        let mut random = Random::new(0x510E527FADE682D1);
+
+        for _ in 0..100 {
+            let clauses = random.clauses(8, 24, 3);
+            let mut solver = CdclSolver::new();
+
+            for clause in clauses.iter() {
+                solver.add_clause(clause);
+            }
+
+            for _ in 0..5 {
+                let assumptions: Vec<Literal> = (0..random.below(5))
+                    .map(|_| Literal::new(random.below(8), random.below(2) == 0))
+                    .collect();
+
+                let mut assumed = clauses.clone();
+
+                match solver.solve_with_assumptions(&assumptions) {
+                    Ok (values) => {
+                        assert!(satisfied(&clauses, &values));
+                        assert!(assumptions.iter().all(|literal| values[literal.variable()] == literal.is_positive()));
+                    }
+
+                    Err (failed) => {
+                        assert!(failed.iter().all(|literal| assumptions.contains(literal)));
+
+                        assumed.extend(failed.iter().map(|&literal| vec![literal]));
+
+                        assert!(!brute_force(&assumed, 8));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::cnf::{Cnf, Literal};
+use crate::expression::Expression;
+
+use super::{CdclSolver, Model};
+
+/// Enumerates the minimal correction sets of a list of named constraints:
+/// the minimal subsets whose removal makes the others satisfiable.
+///
+/// Every constraint `c` gets a relaxation variable `r` with the clause
+/// `r | c`, and the sets are searched by increasing size under a bound on
+/// the number of relaxed constraints, so the first set reported is a
+/// smallest one, as MaxSAT would give. Every set is blocked by requiring
+/// one of its constraints to hold once it has been reported.
+pub struct CorrectionSets<'a> {
+    constraints: &'a [(String, Expression)],
+}
+
+impl<'a> CorrectionSets<'a> {
+    pub fn new (constraints: &'a [(String, Expression)]) -> CorrectionSets<'a> {
+        CorrectionSets {
+            constraints,
+        }
+    }
+
+    /// Streams the correction sets by increasing size, searching for the
+    /// next one on demand.
+    pub fn corrections (&self) -> Corrections<'a> {
+        Corrections {
+            constraints: self.constraints,
+            bound: 0,
+            blocking: Vec::new(),
+            search: None,
+            done: false,
+        }
+    }
+}
+
+/// A minimal correction set, as the names of its constraints in the order
+/// they were given, with an assignment satisfying every other constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction<'a> {
+    pub names: Vec<&'a str>,
+    pub model: Model,
+}
+
+/// The correction sets of a [`CorrectionSets`].
+pub struct Corrections<'a> {
+    constraints: &'a [(String, Expression)],
+    bound: usize,
+
+    /// The reported sets, as indices of their constraints.
+    blocking: Vec<Vec<usize>>,
+
+    search: Option<Search>,
+    done: bool,
+}
+
+/// A solver allowing a bounded number of relaxed constraints.
+struct Search {
+    solver: CdclSolver,
+    cnf: Cnf,
+    literals: Vec<Literal>,
+    relaxations: Vec<Literal>,
+}
+
+impl<'a> Corrections<'a> {
+    fn search (&self) -> Search {
+        let mut cnf = Cnf::new();
+
+        let literals: Vec<Literal> = self.constraints.iter()
+            .map(|(_, expression)| cnf.encode(expression))
+            .collect();
+
+        let relaxations: Vec<Literal> = literals.iter()
+            .map(|&literal| {
+                let relaxation = Literal::positive(cnf.fresh());
+
+                cnf.add_clause(vec![relaxation, literal]);
+
+                relaxation
+            })
+            .collect();
+
+        cnf.add_at_most(&relaxations, self.bound);
+
+        for set in self.blocking.iter() {
+            cnf.add_clause(set.iter().map(|&index| !relaxations[index]).collect());
+        }
+
+        Search {
+            solver: CdclSolver::from_cnf(&cnf),
+            cnf,
+            literals,
+            relaxations,
+        }
+    }
+}
+
+impl<'a> Iterator for Corrections<'a> {
+    type Item = Correction<'a>;
+
+    fn next (&mut self) -> Option<Correction<'a>> {
+        loop {
+            if self.done {
+                return None;
+            }
+
+            if self.search.is_none() {
+                self.search = Some(self.search());
+            }
+
+            let Search { solver, cnf, literals, relaxations } = self.search.as_mut()?;
+
+            let Some (values) = solver.solve() else {
+                self.search = None;
+                self.bound += 1;
+                self.done = self.bound > self.constraints.len();
+
+                continue;
+            };
+
+            let set: Vec<usize> = literals.iter()
+                .enumerate()
+                .filter(|(_, literal)| values[literal.variable()] != literal.is_positive())
+                .map(|(index, _)| index)
+                .collect();
+
+            // Nothing to correct: the constraints hold together.
+            if set.is_empty() {
+                self.done = true;
+            }
+
+            solver.add_clause(&set.iter().map(|&index| !relaxations[index]).collect::<Vec<_>>());
+
+            let correction = Correction {
+                names: set.iter().map(|&index| self.constraints[index].0.as_str()).collect(),
+                model: cnf.model(&values),
+            };
+
+            self.blocking.push(set);
+
+            return Some(correction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::satisfability::UnsatisfiableCore;
+    use crate::testing::{Random, constraints, evaluate};
+
+    #[test]
+    fn should_enumerate_by_increasing_size () {
+        let constraints = constraints(&["a", "b", "¬a | ¬b", "a -> c", "¬c"]);
+
+        let names: Vec<Vec<&str>> = CorrectionSets::new(&constraints).corrections()
+            .map(|correction| correction.names)
+            .collect();
+
+        assert_eq!(names[0], vec!["a"]);
+
+        let mut rest = names[1..].to_vec();
+
+        rest.sort();
+
+        assert_eq!(rest, vec![
+            vec!["b", "a -> c"],
+            vec!["b", "¬c"],
+            vec!["¬a | ¬b", "a -> c"],
+            vec!["¬a | ¬b", "¬c"],
+        ]);
+    }
+
+    #[test]
+    fn models_should_satisfy_the_kept_constraints () {
+        let constraints = constraints(&["a", "¬a", "a -> b", "¬b", "c"]);
+
+        let corrections: Vec<Correction> = CorrectionSets::new(&constraints).corrections().collect();
+
+        assert_eq!(corrections.len(), 3);
+
+        for correction in corrections {
+            for (name, expression) in constraints.iter() {
+                let kept = !correction.names.contains(&name.as_str());
+
+                assert_eq!(evaluate(expression, &correction.model), kept, "{:?} {}", correction, name);
+            }
+        }
+    }
+
+    #[test]
+    fn satisfiable_constraints_should_need_no_correction () {
+        let constraints = constraints(&["a | b", "¬a"]);
+
+        let corrections: Vec<Correction> = CorrectionSets::new(&constraints).corrections().collect();
+
+        assert_eq!(corrections.len(), 1);
+        assert!(corrections[0].names.is_empty());
+        assert_eq!(corrections[0].model.get("b"), Some(&true));
+    }
+
+    #[test]
+    fn every_core_should_meet_every_correction () {
+        let names = ["a", "b", "c"];
+        let mut random = Random::new(0x5BE0CD19137E2179);
+
+        for _ in 0..30 {
+            let constraints: Vec<(String, Expression)> = (0..6)
+                .map(|index| (format!("c{}", index), random.expression(&names, 2)))
+                .collect();
+
+            let corrections: Vec<Correction> = CorrectionSets::new(&constraints).corrections().collect();
+
+            assert!(corrections.windows(2).all(|pair| pair[0].names.len() <= pair[1].names.len()));
+
+            if let Some (core) = UnsatisfiableCore::new(&constraints).minimal() {
+                for correction in corrections.iter() {
+                    assert!(correction.names.iter().any(|name| core.contains(name)), "{:?} {:?}", core, correction);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use crate::cnf::{Clause, Cnf, Literal};
+use crate::expression::Expression;
+use crate::natural::Natural;
+
+use super::CdclSolver;
+
+/// Counts the assignments satisfying an expression exactly.
+///
+/// The expression is Tseitin encoded, whose auxiliary variables are fixed
+/// by the named ones, and the clauses are counted by a DPLL search that
+/// branches on projected variables only, splits the clauses into
+/// independent components and caches the count of every component.
+pub struct ModelCounter<'a> {
+    expression: &'a Expression,
+    projection: Option<Vec<String>>,
+}
+
+impl<'a> ModelCounter<'a> {
+    pub fn new (expression: &'a Expression) -> ModelCounter<'a> {
+        ModelCounter {
+            expression,
+            projection: None,
+        }
+    }
+
+    /// Counts the assignments of the given variables that extend to a
+    /// model, instead of the models over every variable of the expression.
+    pub fn project<I, S> (mut self, names: I) -> ModelCounter<'a>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.projection = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn count (&self) -> Natural {
+        let mut cnf = Cnf::from_expression(self.expression);
+
+        let names: Vec<String> = match &self.projection {
+            Some (names) => names.clone(),
+            None => self.expression.variables().into_iter().collect(),
+        };
+
+        let mut scope: Vec<usize> = names.iter().map(|name| cnf.variable(name)).collect();
+
+        scope.sort();
+        scope.dedup();
+
+        let mut projected = vec![false; cnf.variables()];
+
+        for &variable in scope.iter() {
+            projected[variable] = true;
+        }
+
+        let mut counter = Counter {
+            projected,
+            cache: HashMap::new(),
+        };
+
+        counter.count(cnf.clauses().to_vec(), &scope)
+    }
+}
+
+struct Counter {
+    projected: Vec<bool>,
+    cache: HashMap<Vec<Clause>, Natural>,
+}
+
+impl Counter {
+    /// Number of assignments of the `scope` variables that extend to a
+    /// model of `clauses`.
+    fn count (&mut self, clauses: Vec<Clause>, scope: &[usize]) -> Natural {
+        let Some ((clauses, assigned)) = propagate(clauses) else {
+            return Natural::zero();
+        };
+
+        let mut constrained = vec![false; self.projected.len()];
+
+        for clause in clauses.iter() {
+            for literal in clause {
+                constrained[literal.variable()] = true;
+            }
+        }
+
+        for literal in assigned {
+            constrained[literal.variable()] = true;
+        }
+
+        let free = scope.iter().filter(|&&variable| !constrained[variable]).count();
+
+        let mut count = Natural::power_of_two(free);
+
+        for component in components(clauses) {
+            count *= &self.component(component);
+
+            if count.is_zero() {
+                break;
+            }
+        }
+
+        count
+    }
+
+    fn component (&mut self, mut clauses: Vec<Clause>) -> Natural {
+        for clause in clauses.iter_mut() {
+            clause.sort();
+        }
+
+        clauses.sort();
+
+        if let Some (count) = self.cache.get(&clauses) {
+            return count.clone();
+        }
+
+        let mut occurrences: HashMap<usize, usize> = HashMap::new();
+
+        for literal in clauses.iter().flatten() {
+            if self.projected[literal.variable()] {
+                *occurrences.entry(literal.variable()).or_default() += 1;
+            }
+        }
+
+        let branch = occurrences.iter()
+            .max_by_key(|&(&variable, &count)| (count, std::cmp::Reverse(variable)))
+            .map(|(&variable, _)| variable);
+
+        let count = match branch {
+            // Only existentially quantified variables are left.
+            None => {
+                let mut solver = CdclSolver::new();
+
+                for clause in clauses.iter() {
+                    solver.add_clause(clause);
+                }
+
+                if solver.solve().is_some() { Natural::one() } else { Natural::zero() }
+            }
+
+            Some (variable) => {
+                let mut scope: Vec<usize> = occurrences.into_keys().filter(|&other| other != variable).collect();
+
+                scope.sort();
+
+                let positive = condition(&clauses, Literal::positive(variable));
+                let negative = condition(&clauses, Literal::negative(variable));
+
+                self.count(positive, &scope) + self.count(negative, &scope)
+            }
+        };
+
+        self.cache.insert(clauses, count.clone());
+
+        count
+    }
+}
+
+/// Clauses that remain once `literal` is true.
+fn condition (clauses: &[Clause], literal: Literal) -> Vec<Clause> {
+    clauses.iter()
+        .filter(|clause| !clause.contains(&literal))
+        .map(|clause| clause.iter().copied().filter(|&other| other != !literal).collect())
+        .collect()
+}
+
+/// Assigns unit clauses until none is left, returning the remaining
+/// clauses and the assigned literals, or `None` on a conflict.
+fn propagate (mut clauses: Vec<Clause>) -> Option<(Vec<Clause>, Vec<Literal>)> {
+    let mut assigned = Vec::new();
+
+    loop {
+        if clauses.iter().any(|clause| clause.is_empty()) {
+            return None;
+        }
+
+        match clauses.iter().find(|clause| clause.len() == 1) {
+            Some (unit) => {
+                let literal = unit[0];
+
+                assigned.push(literal);
+                clauses = condition(&clauses, literal);
+            }
+
+            None => return Some((clauses, assigned)),
+        }
+    }
+}
+
+/// Splits clauses into groups that share no variable.
+fn components (clauses: Vec<Clause>) -> Vec<Vec<Clause>> {
+    let mut parents: HashMap<usize, usize> = HashMap::new();
+
+    fn find (parents: &mut HashMap<usize, usize>, variable: usize) -> usize {
+        let parent = *parents.entry(variable).or_insert(variable);
+
+        if parent == variable {
+            return variable;
+        }
+
+        let root = find(parents, parent);
+
+        parents.insert(variable, root);
+
+        root
+    }
+
+    for clause in clauses.iter() {
+        let first = find(&mut parents, clause[0].variable());
+
+        for literal in clause[1..].iter() {
+            let root = find(&mut parents, literal.variable());
+
+            parents.insert(root, first);
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<Clause>> = HashMap::new();
+
+    for clause in clauses {
+        let root = find(&mut parents, clause[0].variable());
+
+        groups.entry(root).or_default().push(clause);
+    }
+
+    groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::satisfability::AllSatisfability;
+    use crate::testing::Random;
+
+    #[test]
+    fn should_count_simple_formulas () {
+        let count = |string: &str| ModelCounter::new(&parse(string).unwrap()).count().to_u64();
+
+        assert_eq!(count("a | b"), Some(3));
+        assert_eq!(count("a ^ b ^ c"), Some(4));
+        assert_eq!(count("a & ¬a"), Some(0));
+        assert_eq!(count("true"), Some(1));
+        assert_eq!(count("a | ¬a"), Some(2));
+    }
+
+    #[test]
+    fn should_count_projected_and_free_variables () {
+        let expression = parse("(a | b) & c").unwrap();
+
+        let count = |names: &[&str]| ModelCounter::new(&expression).project(names.iter().copied()).count().to_u64();
+
+        assert_eq!(count(&["a"]), Some(2));
+        assert_eq!(count(&["c"]), Some(1));
+        assert_eq!(count(&["a", "d"]), Some(4));
+        assert_eq!(count(&[]), Some(1));
+    }
+
+    #[test]
+    fn should_count_beyond_u64 () {
+        let clauses: Vec<String> = (0..100).map(|index| format!("(x{} | y{})", index, index)).collect();
+
+        let expression = parse(&clauses.join(" & ")).unwrap();
+
+        assert_eq!(
+            ModelCounter::new(&expression).count().to_string(),
+            "515377520732011331036461129765621272702107522001",
+        );
+    }
+
+    #[test]
+    fn should_agree_with_enumeration () {
+        let names = ["a", "b", "c", "d", "e"];
+        let mut random = Random::new(0x3C6EF372FE94F82B);
+
+        for _ in 0..100 {
+            let expression = random.expression(&names, 6);
+
+            assert_eq!(
+                ModelCounter::new(&expression).count().to_u64(),
+                Some(AllSatisfability::new(&expression).models().count() as u64),
+                "{:?}", expression,
+            );
+
+            let projection = &names[..random.below(names.len()) + 1];
+
+            assert_eq!(
+                ModelCounter::new(&expression).project(projection.iter().copied()).count().to_u64(),
+                Some(AllSatisfability::new(&expression).project(projection.iter().copied()).models().count() as u64),
+                "{:?} {:?}", expression, projection,
+            );
+        }
+    }
+}
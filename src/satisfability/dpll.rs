@@ -0,0 +1,317 @@
+use crate::cnf::{Cnf, Clause, Literal};
+use crate::expression::Expression;
+
+use super::Model;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Status {
+    Satisfied,
+    Falsified,
+    Unit (Literal),
+    Unresolved,
+}
+
+/// A textbook DPLL solver: unit propagation, pure literal elimination and
+/// chronological backtracking over the clauses of a [`Cnf`].
+///
+/// It rescans every clause at each step, so it is only meant as a simple,
+/// readable oracle to cross-check the other solvers on small formulas.
+#[derive(Debug, Clone)]
+pub struct DpllSolver {
+    clauses: Vec<Clause>,
+    values: Vec<Option<bool>>,
+    trail: Vec<usize>,
+}
+
+impl DpllSolver {
+    pub fn new (cnf: &Cnf) -> DpllSolver {
+        DpllSolver {
+            clauses: cnf.clauses().to_vec(),
+            values: vec![None; cnf.variables()],
+            trail: Vec::new(),
+        }
+    }
+
+    /// Searches for an assignment satisfying every clause, indexed by variable.
+    pub fn solve (mut self) -> Option<Vec<bool>> {
+        if self.search() {
+            Some(self.values.iter().map(|value| value.unwrap_or(false)).collect())
+        } else {
+            None
+        }
+    }
+
+    fn search (&mut self) -> bool {
+        let mark = self.trail.len();
+
+        if !self.propagate() {
+            self.undo(mark);
+
+            return false;
+        }
+
+        self.eliminate_pure_literals();
+
+        let variable = match self.pick_variable() {
+            Some (variable) => variable,
+            None => return true,
+        };
+
+        for value in [true, false] {
+            let decision = self.trail.len();
+
+            self.assign(Literal::new(variable, value));
+
+            if self.search() {
+                return true;
+            }
+
+            self.undo(decision);
+        }
+
+        self.undo(mark);
+
+        false
+    }
+
+    fn value (&self, literal: Literal) -> Option<bool> {
+        self.values[literal.variable()].map(|value| value == literal.is_positive())
+    }
+
+    fn assign (&mut self, literal: Literal) {
+        self.values[literal.variable()] = Some(literal.is_positive());
+        self.trail.push(literal.variable());
+    }
+
+    fn undo (&mut self, mark: usize) {
+        for variable in self.trail.drain(mark..) {
+            self.values[variable] = None;
+        }
+    }
+
+    fn status (&self, clause: &Clause) -> Status {
+        let mut unassigned = None;
+        let mut count = 0;
+
+        for &literal in clause {
+            match self.value(literal) {
+                Some (true) => return Status::Satisfied,
+                Some (false) => {}
+                None => {
+                    unassigned = Some(literal);
+                    count += 1;
+                }
+            }
+        }
+
+        match (count, unassigned) {
+            (0, _) => Status::Falsified,
+            (1, Some (literal)) => Status::Unit(literal),
+            _ => Status::Unresolved,
+        }
+    }
+
+    /// Assigns unit literals until a fixpoint, returning `false` on a conflict.
+    fn propagate (&mut self) -> bool {
+        loop {
+            let mut changed = false;
+
+            for index in 0..self.clauses.len() {
+                match self.status(&self.clauses[index]) {
+                    Status::Falsified => return false,
+                    Status::Unit (literal) => {
+                        self.assign(literal);
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    /// Assigns every unassigned literal whose negation appears in no
+    /// unsatisfied clause.
+    fn eliminate_pure_literals (&mut self) {
+        let mut occurs = vec![false; 2 * self.values.len()];
+
+        for clause in self.clauses.iter() {
+            if self.status(clause) == Status::Satisfied {
+                continue;
+            }
+
+            for &literal in clause {
+                if self.value(literal).is_none() {
+                    occurs[literal.index()] = true;
+                }
+            }
+        }
+
+        for variable in 0..self.values.len() {
+            let positive = Literal::positive(variable);
+            let negative = Literal::negative(variable);
+
+            match (occurs[positive.index()], occurs[negative.index()]) {
+                (true, false) => self.assign(positive),
+                (false, true) => self.assign(negative),
+                _ => {}
+            }
+        }
+    }
+
+    fn pick_variable (&self) -> Option<usize> {
+        self.clauses.iter()
+            .filter(|clause| self.status(clause) != Status::Satisfied)
+            .flat_map(|clause| clause.iter())
+            .find(|&&literal| self.value(literal).is_none())
+            .map(|literal| literal.variable())
+    }
+}
+
+pub struct DpllSatisfability<'a> {
+    expression: &'a Expression,
+}
+
+impl<'a> DpllSatisfability<'a> {
+    pub fn new (expression: &'a Expression) -> DpllSatisfability<'a> {
+        DpllSatisfability {
+            expression
+        }
+    }
+
+    /// Finds an assignment making the expression evaluate to `expectative`.
+    pub fn satisfies (&self, expectative: bool) -> Option<Model> {
+        let mut cnf = Cnf::new();
+
+        let literal = cnf.encode(self.expression);
+
+        cnf.add_clause(vec![if expectative { literal } else { !literal }]);
+
+        DpllSolver::new(&cnf)
+            .solve()
+            .map(|values| cnf.model(&values))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::satisfability::{CdclSatisfability, DynamicSatisfability, Requirement};
+    use crate::testing::{Random, evaluate, brute_force, satisfied};
+
+    #[test]
+    fn and_expression_should_satisfies_true () {
+        let expression = Expression::And(
+            Box::new(Expression::var("a")),
+            Box::new(Expression::Not(Box::new(Expression::var("b")))),
+        );
+
+        assert_eq!(
+            DpllSatisfability::new(&expression).satisfies(true),
+            Some(Model::from([("a".to_string(), true), ("b".to_string(), false)])),
+        );
+    }
+
+    #[test]
+    fn contradiction_should_not_be_satisfiable () {
+        let expression = Expression::And(
+            Box::new(Expression::var("a")),
+            Box::new(Expression::Not(Box::new(Expression::var("a")))),
+        );
+
+        assert_eq!(DpllSatisfability::new(&expression).satisfies(true), None);
+        assert_eq!(DynamicSatisfability::new(&expression).satisfies(true), Requirement::Never);
+    }
+
+    #[test]
+    fn should_agree_with_brute_force_on_random_clauses () {
+        let mut random = Random::new(0x853C49E6748FEA9B);
+
+        for _ in 0..200 {
+            let clauses = random.clauses(7, 30, 3);
+
+            let mut cnf = Cnf::new();
+
+            for variable in 0..7 {
+                cnf.variable(variable.to_string());
+            }
+
+            for clause in clauses.iter() {
+                cnf.add_clause(clause.clone());
+            }
+
+            match DpllSolver::new(&cnf).solve() {
+                Some (values) => assert!(satisfied(&clauses, &values)),
+                None => assert!(!brute_force(&clauses, 7)),
+            }
+        }
+    }
+
+    #[test]
+    fn should_agree_with_cdcl_on_random_expressions () {
+        let mut random = Random::new(0xDA942042E4DD58B5);
+
+        for _ in 0..300 {
+            let expression = random.expression(&["a", "b", "c", "d"], 5);
+
+            for expectative in [true, false] {
+                let dpll = DpllSatisfability::new(&expression).satisfies(expectative);
+                let cdcl = CdclSatisfability::new(&expression).satisfies(expectative);
+
+                assert_eq!(dpll.is_some(), cdcl.is_some(), "{:?}", expression);
+
+                if let Some (model) = dpll {
+                    assert_eq!(evaluate(&expression, &model), expectative, "{:?}", expression);
+                }
+            }
+        }
+    }
+
+    /// Whether the model meets every binding the requirement asks for.
+    fn fulfils (requirement: &Requirement, model: &Model) -> bool {
+        match requirement {
+            Requirement::Var (name, value) => model.get(name) == Some(value),
+            Requirement::All (left, right) => fulfils(left, model) && fulfils(right, model),
+            Requirement::Any (left, right) => fulfils(left, model) || fulfils(right, model),
+            Requirement::Always => true,
+            Requirement::Never => false,
+        }
+    }
+
+    #[test]
+    fn should_agree_with_dynamic_on_random_expressions () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0x9E3779B97F4A7C15);
+
+        let models: Vec<Model> = (0..1u32 << names.len())
+            .map(|bits| {
+                names.iter()
+                    .enumerate()
+                    .map(|(index, name)| (name.to_string(), bits & (1 << index) != 0))
+                    .collect()
+            })
+            .collect();
+
+        for _ in 0..300 {
+            let expression = random.expression(&names, 4);
+
+            for expectative in [true, false] {
+                let dpll = DpllSatisfability::new(&expression).satisfies(expectative);
+                let requirement = DynamicSatisfability::new(&expression).satisfies(expectative);
+
+                assert_eq!(dpll.is_some(), models.iter().any(|model| fulfils(&requirement, model)), "{:?}", expression);
+
+                if let Some (mut model) = dpll {
+                    for name in names {
+                        model.entry(name.to_string()).or_insert(false);
+                    }
+
+                    assert!(fulfils(&requirement, &model), "{:?} {:?}", expression, requirement);
+                }
+            }
+        }
+    }
+}
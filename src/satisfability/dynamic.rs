@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::expression::Expression;
 
 /*
@@ -174,32 +176,22 @@ impl Requirement {
         }
     }
 
+    #[deprecated(note = "print the `Display` form of the requirement instead")]
     pub fn format (self) {
-        match self {
-            Requirement::Any (left, right) => {
-                left.format();
-
-                // println!();
-                println!();
-
-                right.format();
-                
-                // println!();
-            }
-
-            Requirement::All (left, right) => {
-                left.format();
-
-                print!(", ");
-
-                right.format()
-            }
-
-            Requirement::Var(name, value) => {
-                print!("{} -> {}", name, value);
-            }
+        print!("{}", self);
+    }
+}
 
-            req => unimplemented!("{:?}", req),
+/// Lists the alternatives of a requirement one per line, each as the
+/// comma separated bindings it needs.
+impl fmt::Display for Requirement {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Requirement::Any (left, right) => write!(formatter, "{}\n{}", left, right),
+            Requirement::All (left, right) => write!(formatter, "{}, {}", left, right),
+            Requirement::Var (name, value) => write!(formatter, "{} -> {}", name, value),
+            Requirement::Always => write!(formatter, "always"),
+            Requirement::Never => write!(formatter, "never"),
         }
     }
 }
@@ -279,6 +271,40 @@ impl<'a> DynamicSatisfability<'a> {
                 }                            
             }
 
+            Expression::Implies (left, right) => {
+                if expectative {
+                    Requirement::any(
+                        self.satisfies_expression(left, false),
+                        self.satisfies_expression(right, true),
+                    )
+                } else {
+                    Requirement::all(
+                        self.satisfies_expression(left, true),
+                        self.satisfies_expression(right, false),
+                    )
+                }
+            }
+
+            Expression::Iff (left, right) => {
+                let left_true = self.satisfies_expression(left, true);
+                let left_false = self.satisfies_expression(left, false);
+
+                let right_true = self.satisfies_expression(right, true);
+                let right_false = self.satisfies_expression(right, false);
+
+                if expectative {
+                    Requirement::any(
+                        Requirement::all(left_true, right_true),
+                        Requirement::all(left_false, right_false),
+                    )
+                } else {
+                    Requirement::any(
+                        Requirement::all(left_true, right_false),
+                        Requirement::all(left_false, right_true),
+                    )
+                }
+            }
+
             Expression::False => match expectative {
                 true => Requirement::Never,
                 false => Requirement::Always,
@@ -307,11 +333,26 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn should_display_alternatives_on_their_own_lines () {
+        let requirement = Requirement::Any(
+            Box::new(Requirement::All(
+                Box::new(Requirement::Var("a".to_string(), true)),
+                Box::new(Requirement::Var("b".to_string(), false)),
+            )),
+            Box::new(Requirement::Var("c".to_string(), true)),
+        );
+
+        assert_eq!(requirement.to_string(), "a -> true, b -> false\nc -> true");
+        assert_eq!(Requirement::Always.to_string(), "always");
+        assert_eq!(Requirement::Never.to_string(), "never");
+    }
+
     #[test]
     fn var_expression_should_satisfies_true () {
         let expression = Expression::Var("a".to_string());
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(satisfability.satisfies(true), Requirement::Var("a".to_string(), true));
     }
@@ -320,7 +361,7 @@ mod test {
     fn var_expression_should_satisfies_false () {
         let expression = Expression::Var("a".to_string());
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(satisfability.satisfies(false), Requirement::Var("a".to_string(), false));
     }
@@ -333,7 +374,7 @@ mod test {
             )
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(satisfability.satisfies(true), Requirement::Var("a".to_string(), false));
     }
@@ -346,7 +387,7 @@ mod test {
             )
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(satisfability.satisfies(false), Requirement::Var("a".to_string(), true));
     }
@@ -362,7 +403,7 @@ mod test {
             ),
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(
             satisfability.satisfies(true),
@@ -385,7 +426,7 @@ mod test {
             ),
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(
             satisfability.satisfies(false),
@@ -408,7 +449,7 @@ mod test {
             ),
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(
             satisfability.satisfies(true),
@@ -431,7 +472,7 @@ mod test {
             ),
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
 
         assert_eq!(
             satisfability.satisfies(false),
@@ -458,7 +499,7 @@ mod test {
             )
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(
             satisfability.satisfies(true),
@@ -485,7 +526,7 @@ mod test {
             )
         );
 
-        let mut satisfability = DynamicSatisfability::new(&expression);
+        let satisfability = DynamicSatisfability::new(&expression);
         
         assert_eq!(
             satisfability.satisfies(false),
@@ -496,4 +537,48 @@ mod test {
             )
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn implies_expression_should_satisfies_false () {
+        let expression = Expression::implies(
+            Expression::var("a"),
+            Expression::var("b"),
+        );
+
+        let satisfability = DynamicSatisfability::new(&expression);
+
+        assert_eq!(
+            satisfability.satisfies(false),
+
+            Requirement::All(
+                Box::new(Requirement::Var("a".to_string(), true)),
+                Box::new(Requirement::Var("b".to_string(), false)),
+            )
+        );
+    }
+
+    #[test]
+    fn iff_expression_should_satisfies_true () {
+        let expression = Expression::iff(
+            Expression::var("a"),
+            Expression::var("b"),
+        );
+
+        let satisfability = DynamicSatisfability::new(&expression);
+
+        assert_eq!(
+            satisfability.satisfies(true),
+
+            Requirement::Any(
+                Box::new(Requirement::All(
+                    Box::new(Requirement::Var("a".to_string(), true)),
+                    Box::new(Requirement::Var("b".to_string(), true)),
+                )),
+                Box::new(Requirement::All(
+                    Box::new(Requirement::Var("a".to_string(), false)),
+                    Box::new(Requirement::Var("b".to_string(), false)),
+                )),
+            )
+        );
+    }
+}
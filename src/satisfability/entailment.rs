@@ -0,0 +1,108 @@
+use crate::expression::Expression;
+
+use super::{CdclSatisfability, Model};
+
+/// Decides whether a set of premises entails a goal, by asking the CDCL
+/// solver for a model of the premises in which the goal is false.
+///
+/// Inconsistent premises entail every goal.
+pub struct Entailment<'a> {
+    premises: &'a [Expression],
+    goal: &'a Expression,
+}
+
+impl<'a> Entailment<'a> {
+    pub fn new (premises: &'a [Expression], goal: &'a Expression) -> Entailment<'a> {
+        Entailment {
+            premises,
+            goal,
+        }
+    }
+
+    pub fn holds (&self) -> bool {
+        self.counterexample().is_none()
+    }
+
+    /// An assignment satisfying every premise but not the goal, if any.
+    pub fn counterexample (&self) -> Option<Model> {
+        let refutation = Expression::from_expressions(
+            self.premises.iter().cloned().chain([Expression::not(self.goal.clone())])
+        ).expect("refutation should contain the negated goal");
+
+        CdclSatisfability::new(&refutation).satisfies(true)
+    }
+}
+
+impl Expression {
+    /// Whether every assignment satisfying `self` satisfies `goal` too.
+    pub fn entails (&self, goal: &Expression) -> bool {
+        Entailment::new(std::slice::from_ref(self), goal).holds()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::testing::{Random, evaluate};
+
+    fn expressions (strings: &[&str]) -> Vec<Expression> {
+        strings.iter().map(|string| parse(string).unwrap()).collect()
+    }
+
+    #[test]
+    fn should_chain_rules () {
+        let premises = expressions(&["a -> b", "b -> c", "a"]);
+
+        assert!(Entailment::new(&premises, &parse("c").unwrap()).holds());
+        assert!(Entailment::new(&premises, &parse("a & b & c").unwrap()).holds());
+        assert!(!Entailment::new(&premises[..2], &parse("c").unwrap()).holds());
+    }
+
+    #[test]
+    fn should_report_counter_model () {
+        let premises = expressions(&["a | b", "¬a | c"]);
+        let goal = parse("c").unwrap();
+
+        let model = Entailment::new(&premises, &goal).counterexample().unwrap();
+
+        assert!(premises.iter().all(|premise| evaluate(premise, &model)));
+        assert!(!evaluate(&goal, &model));
+        assert_eq!(model.get("b"), Some(&true));
+    }
+
+    #[test]
+    fn should_handle_empty_and_inconsistent_premises () {
+        let goal = parse("a | ¬a").unwrap();
+
+        assert!(Entailment::new(&[], &goal).holds());
+        assert!(!Entailment::new(&[], &parse("a").unwrap()).holds());
+
+        let inconsistent = expressions(&["a", "¬a"]);
+
+        assert!(Entailment::new(&inconsistent, &parse("b").unwrap()).holds());
+        assert!(parse("a & b").unwrap().entails(&parse("a | c").unwrap()));
+    }
+
+    #[test]
+    fn should_agree_with_enumeration () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0x9B05688C2B3E6C1F);
+
+        for _ in 0..100 {
+            let premises: Vec<Expression> = (0..random.below(3) + 1).map(|_| random.expression(&names, 3)).collect();
+            let goal = random.expression(&names, 3);
+
+            let refuted = (0..1u32 << names.len()).all(|bits| {
+                let model: Model = names.iter()
+                    .enumerate()
+                    .map(|(index, name)| (name.to_string(), bits & (1 << index) != 0))
+                    .collect();
+
+                !premises.iter().all(|premise| evaluate(premise, &model)) || evaluate(&goal, &model)
+            });
+
+            assert_eq!(Entailment::new(&premises, &goal).holds(), refuted, "{:?} {:?}", premises, goal);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::expression::Expression;
+
+use super::{CdclSatisfability, Model};
+
+/// Decides whether two expressions are logically equivalent by asking the
+/// CDCL solver to make their miter `left ^ right` true.
+pub struct Equivalence<'a> {
+    left: &'a Expression,
+    right: &'a Expression,
+}
+
+impl<'a> Equivalence<'a> {
+    pub fn new (left: &'a Expression, right: &'a Expression) -> Equivalence<'a> {
+        Equivalence {
+            left,
+            right,
+        }
+    }
+
+    pub fn holds (&self) -> bool {
+        self.counterexample().is_none()
+    }
+
+    /// An assignment of the variables of both expressions under which they
+    /// evaluate differently, if any.
+    pub fn counterexample (&self) -> Option<Model> {
+        let miter = Expression::xor(self.left.clone(), self.right.clone());
+
+        CdclSatisfability::new(&miter).satisfies(true)
+    }
+}
+
+impl Expression {
+    /// Whether both expressions take the same value under every assignment.
+    pub fn is_equivalent (&self, other: &Expression) -> bool {
+        Equivalence::new(self, other).holds()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::testing::{Random, evaluate};
+    use crate::truth_table::TruthTable;
+
+    #[test]
+    fn should_prove_textbook_laws () {
+        let laws = [
+            ("a -> b", "¬a | b"),
+            ("¬(a & b)", "¬a | ¬b"),
+            ("a & (¬a | b)", "a & b"),
+            ("a ^ b", "(a | b) & ¬(a & b)"),
+            ("a <-> b", "¬(a ^ b)"),
+            ("a | (b & c)", "(a | b) & (a | c)"),
+        ];
+
+        for (left, right) in laws {
+            assert!(parse(left).unwrap().is_equivalent(&parse(right).unwrap()), "{} {}", left, right);
+        }
+    }
+
+    #[test]
+    fn should_distinguish_xor_from_or () {
+        let left = parse("a ^ b").unwrap();
+        let right = parse("a | b").unwrap();
+
+        let model = Equivalence::new(&left, &right).counterexample().unwrap();
+
+        assert_eq!(model, Model::from([("a".to_string(), true), ("b".to_string(), true)]));
+    }
+
+    #[test]
+    fn should_compare_expressions_over_different_variables () {
+        let left = parse("a | (b & ¬b)").unwrap();
+        let right = parse("a").unwrap();
+
+        assert!(left.is_equivalent(&right));
+
+        let model = Equivalence::new(&right, &parse("a & c").unwrap()).counterexample().unwrap();
+
+        assert_eq!(model.get("a"), Some(&true));
+        assert_eq!(model.get("c"), Some(&false));
+    }
+
+    #[test]
+    fn should_agree_with_truth_tables () {
+        let names = ["a", "b", "c"];
+        let mut random = Random::new(0x510E527FADE682D1);
+
+        for _ in 0..100 {
+            let left = random.expression(&names, 4);
+            let right = random.expression(&names, 4);
+
+            let miter = Expression::xor(left.clone(), right.clone());
+            let differ = TruthTable::new(&miter).unwrap().rows().any(|(_, value)| value);
+
+            match Equivalence::new(&left, &right).counterexample() {
+                Some (model) => assert_ne!(evaluate(&left, &model), evaluate(&right, &model), "{:?} {:?}", left, right),
+                None => assert!(!differ, "{:?} {:?}", left, right),
+            }
+        }
+    }
+}
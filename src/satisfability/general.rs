@@ -1,10 +1,15 @@
 use crate::expression::Expression;
 
+use super::{CdclSatisfability, Model};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Expectative {
     True,
     False,
 }
 
+/// Answers whether an expression can be made true (is satisfiable) or
+/// made false (is falsifiable), using the CDCL solver.
 pub struct GeneralSatisfability<'a> {
     expression: &'a Expression,
 }
@@ -16,55 +21,18 @@ impl<'a> GeneralSatisfability<'a> {
         }
     }
 
-    fn satisfies_expression (&self, expression: &Expression, expectative: Expectative) -> bool {
-        match expression {
-            Expression::Var (_) => true,
-
-            Expression::True => match expectative {
-                Expectative::True => true,
-                Expectative::False => false,
-            }
-
-            Expression::False => match expectative {
-                Expectative::False => true,
-                Expectative::True => false,
-            }
-
-            Expression::Not (inner) => {
-                self.satisfies_expression(inner, match expectative {
-                    Expectative::True => Expectative::False,
-                    Expectative::False => Expectative::True,
-                })
-            }
-
-            Expression::Or(left, right) => {
-                let left_true = self.satisfies_expression(left, Expectative::True);
-                let right_true = self.satisfies_expression(right, Expectative::True);
-
-                left_true || right_true
-            }
-
-            Expression::And(left, right) => {
-                let left_true = self.satisfies_expression(left, Expectative::True);
-                let right_true = self.satisfies_expression(right, Expectative::True);
-
-                left_true && right_true
-            }
-
-            Expression::Xor (left, right)=> {
-                let left_true = self.satisfies_expression(left, Expectative::True);
-                let left_false = self.satisfies_expression(left, Expectative::False);
-
-                let right_true = self.satisfies_expression(right, Expectative::True);
-                let right_false = self.satisfies_expression(right, Expectative::False);
-
-                (left_true && right_false) || (left_false && right_true)
-            }
-        }
+    pub fn satisfies (&self, expectative: Expectative) -> bool {
+        self.witness(expectative).is_some()
     }
 
-    pub fn satisfies (&self, expectative: Expectative) -> bool {
-        self.satisfies_expression(self.expression, expectative)
+    /// An assignment under which the expression meets the expectative, if any.
+    pub fn witness (&self, expectative: Expectative) -> Option<Model> {
+        let expectative = match expectative {
+            Expectative::True => true,
+            Expectative::False => false,
+        };
+
+        CdclSatisfability::new(self.expression).satisfies(expectative)
     }
 }
 
@@ -143,4 +111,83 @@ mod test {
         
         assert!(expression.general_satisfability().satisfies(Expectative::False));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn contradiction_should_not_satisfies_true () {
+        let expression = Expression::And(
+            Box::new(
+                Expression::Var("a".to_string())
+            ),
+            Box::new(
+                Expression::Not(
+                    Box::new(
+                        Expression::Var("a".to_string())
+                    )
+                )
+            ),
+        );
+
+        assert!(!expression.general_satisfability().satisfies(Expectative::True));
+        assert!(expression.general_satisfability().satisfies(Expectative::False));
+    }
+
+    #[test]
+    fn tautology_should_not_satisfies_false () {
+        let expression = Expression::Or(
+            Box::new(
+                Expression::Var("a".to_string())
+            ),
+            Box::new(
+                Expression::Not(
+                    Box::new(
+                        Expression::Var("a".to_string())
+                    )
+                )
+            ),
+        );
+
+        assert!(expression.general_satisfability().satisfies(Expectative::True));
+        assert!(!expression.general_satisfability().satisfies(Expectative::False));
+    }
+
+    #[test]
+    fn shared_variables_should_be_consistent () {
+        let expression = Expression::And(
+            Box::new(
+                Expression::Xor(
+                    Box::new(Expression::Var("a".to_string())),
+                    Box::new(Expression::Var("b".to_string())),
+                )
+            ),
+            Box::new(
+                Expression::And(
+                    Box::new(Expression::Var("a".to_string())),
+                    Box::new(Expression::Var("b".to_string())),
+                )
+            ),
+        );
+
+        assert!(!expression.general_satisfability().satisfies(Expectative::True));
+    }
+
+    #[test]
+    fn and_expression_should_have_witness () {
+        let expression = Expression::And(
+            Box::new(
+                Expression::Var("a".to_string())
+            ),
+            Box::new(
+                Expression::Not(
+                    Box::new(
+                        Expression::Var("b".to_string())
+                    )
+                )
+            ),
+        );
+
+        assert_eq!(
+            expression.general_satisfability().witness(Expectative::True),
+            Some(Model::from([("a".to_string(), true), ("b".to_string(), false)])),
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use crate::cnf::{Cnf, Literal};
+use crate::expression::Expression;
+
+use super::{CdclSolver, Model};
+
+/// A solver session over expressions that only ever grows: every
+/// expression added stays asserted, and each search may additionally
+/// assume expressions that are dropped afterwards. Clauses learnt by the
+/// CDCL solver carry over from one search to the next.
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalSatisfability {
+    cnf: Cnf,
+    solver: CdclSolver,
+
+    /// Number of clauses of `cnf` already given to the solver.
+    added: usize,
+
+    /// Literal defined for each assumption so far, keyed by its debug form
+    /// since expressions have no hash.
+    assumptions: HashMap<String, Literal>,
+}
+
+impl IncrementalSatisfability {
+    pub fn new () -> IncrementalSatisfability {
+        IncrementalSatisfability {
+            cnf: Cnf::new(),
+            solver: CdclSolver::new(),
+            added: 0,
+            assumptions: HashMap::new(),
+        }
+    }
+
+    /// Asserts `expression` in every later search.
+    pub fn add (&mut self, expression: &Expression) {
+        let literal = self.cnf.encode(expression);
+
+        self.cnf.add_clause(vec![literal]);
+        self.flush();
+    }
+
+    /// Finds an assignment satisfying every expression added so far.
+    pub fn solve (&mut self) -> Option<Model> {
+        self.solve_with_assumptions(&[]).ok()
+    }
+
+    /// Finds an assignment satisfying every expression added so far and
+    /// every assumption. When there is none, returns the assumptions that
+    /// cannot hold together with the added expressions, which is empty
+    /// when the added expressions are unsatisfiable by themselves.
+    ///
+    /// Variables and negated variables are assumed as they are; any other
+    /// expression is defined by fresh auxiliary variables the first time
+    /// it is assumed, and that definition is reused by later searches.
+    pub fn solve_with_assumptions (&mut self, assumptions: &[Expression]) -> Result<Model, Vec<Expression>> {
+        let literals: Vec<Literal> = assumptions.iter()
+            .map(|assumption| {
+                let cnf = &mut self.cnf;
+
+                *self.assumptions.entry(format!("{:?}", assumption))
+                    .or_insert_with(|| cnf.encode(assumption))
+            })
+            .collect();
+
+        self.flush();
+
+        match self.solver.solve_with_assumptions(&literals) {
+            Ok (values) => Ok(self.cnf.model(&values)),
+
+            Err (failed) => Err(
+                assumptions.iter()
+                    .zip(literals.iter())
+                    .filter(|(_, literal)| failed.contains(literal))
+                    .map(|(assumption, _)| assumption.clone())
+                    .collect()
+            ),
+        }
+    }
+
+    /// Number of conflicts met by every search so far.
+    pub fn conflicts (&self) -> usize {
+        self.solver.conflicts()
+    }
+
+    /// Gives the clauses encoded since the last call to the solver.
+    fn flush (&mut self) {
+        for clause in self.cnf.clauses()[self.added..].iter() {
+            self.solver.add_clause(clause);
+        }
+
+        self.added = self.cnf.clauses().len();
+        self.solver.reserve(self.cnf.variables());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+    use crate::testing::{Random, brute_force, evaluate};
+
+    fn satisfiable (expressions: &[Expression]) -> bool {
+        let cnf = Expression::from_expressions(expressions.iter().cloned())
+            .map(|expression| Cnf::from_expression(&expression))
+            .unwrap_or_default();
+
+        brute_force(cnf.clauses(), cnf.variables())
+    }
+
+    #[test]
+    fn should_toggle_options_one_at_a_time () {
+        let mut session = IncrementalSatisfability::new();
+
+        session.add(&parse("gps -> battery").unwrap());
+        session.add(&parse("¬(battery & light)").unwrap());
+
+        let model = session.solve_with_assumptions(&[parse("gps").unwrap()]).unwrap();
+
+        assert_eq!(model.get("battery"), Some(&true));
+        assert_eq!(model.get("light"), Some(&false));
+
+        let failed = session.solve_with_assumptions(&[parse("radio").unwrap(), parse("gps").unwrap(), parse("light").unwrap()]);
+
+        assert_eq!(failed, Err(vec![parse("gps").unwrap(), parse("light").unwrap()]));
+
+        assert!(session.solve_with_assumptions(&[parse("light").unwrap()]).is_ok());
+
+        session.add(&parse("light").unwrap());
+
+        assert_eq!(session.solve_with_assumptions(&[parse("gps").unwrap()]), Err(vec![parse("gps").unwrap()]));
+        assert_eq!(session.solve().unwrap().get("gps"), Some(&false));
+    }
+
+    #[test]
+    fn inconsistent_expressions_should_fail_without_assumptions () {
+        let mut session = IncrementalSatisfability::new();
+
+        session.add(&parse("a").unwrap());
+        session.add(&parse("¬a").unwrap());
+
+        assert_eq!(session.solve_with_assumptions(&[parse("b").unwrap()]), Err(Vec::new()));
+        assert_eq!(session.solve(), None);
+    }
+
+    #[test]
+    fn should_assume_compound_expressions () {
+        let mut session = IncrementalSatisfability::new();
+
+        session.add(&parse("a ^ b").unwrap());
+
+        assert!(session.solve_with_assumptions(&[parse("a & b").unwrap()]).is_err());
+
+        let model = session.solve_with_assumptions(&[parse("a | ¬b").unwrap()]).unwrap();
+
+        assert_eq!(model.get("a"), Some(&true));
+        assert_eq!(model.get("b"), Some(&false));
+    }
+
+    #[test]
+    fn should_reuse_the_definition_of_a_repeated_assumption () {
+        let mut session = IncrementalSatisfability::new();
+
+        session.add(&parse("a -> b").unwrap());
+
+        let assumption = parse("(a | c) & ¬b").unwrap();
+
+        assert!(session.solve_with_assumptions(std::slice::from_ref(&assumption)).is_ok());
+
+        let variables = session.cnf.variables();
+        let clauses = session.cnf.clauses().len();
+
+        assert!(session.solve_with_assumptions(std::slice::from_ref(&assumption)).is_ok());
+
+        assert_eq!(session.cnf.variables(), variables);
+        assert_eq!(session.cnf.clauses().len(), clauses);
+    }
+
+    #[test]
+    fn failed_assumptions_should_be_unsatisfiable () {
+        let names = ["a", "b", "c", "d", "e"];
+        let mut random = Random::new(0x1F83D9ABFB41BD6B);
+
+        for _ in 0..10 {
+            let mut session = IncrementalSatisfability::new();
+            let mut added = Vec::new();
+
+            for _ in 0..10 {
+                let expression = random.expression(&names, 2);
+
+                if random.below(3) == 0 {
+                    session.add(&expression);
+                    added.push(expression);
+
+                    continue;
+                }
+
+                let assumptions: Vec<Expression> = (0..random.below(4))
+                    .map(|_| {
+                        let name = Expression::var(names[random.below(names.len())]);
+
+                        if random.below(2) == 0 { name } else { Expression::not(name) }
+                    })
+                    .collect();
+
+                match session.solve_with_assumptions(&assumptions) {
+                    Ok (model) => {
+                        assert!(added.iter().chain(assumptions.iter()).all(|expression| evaluate(expression, &model)));
+                    }
+
+                    Err (failed) => {
+                        assert!(failed.iter().all(|assumption| assumptions.contains(assumption)));
+
+                        let core: Vec<Expression> = added.iter().chain(failed.iter()).cloned().collect();
+
+                        assert!(!satisfiable(&core), "{:?}", core);
+                    }
+                }
+            }
+        }
+    }
+}
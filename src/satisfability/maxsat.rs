@@ -0,0 +1,302 @@
+use std::fmt;
+
+use crate::cnf::dimacs::Wcnf;
+use crate::cnf::{Clause, Cnf, Literal, WEIGHT_COUNTER_LIMIT};
+use crate::expression::Expression;
+
+use super::{CdclSolver, Model};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MaxSatError {
+    /// Forbidding the cost of a model needs more clauses than this limit.
+    TooManyClauses (u64),
+}
+
+impl fmt::Display for MaxSatError {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaxSatError::TooManyClauses (limit) => write!(formatter, "bounding the cost of the soft clauses needs more than {} clauses", limit),
+        }
+    }
+}
+
+impl std::error::Error for MaxSatError {}
+
+/// A weighted partial MaxSAT solver: finds an assignment satisfying every
+/// hard clause that minimises the total weight of the falsified soft ones.
+///
+/// Every soft clause is relaxed by a fresh variable, and the search goes
+/// from satisfiable to unsatisfiable: once a model of cost `c` is found,
+/// a generalized totalizer over the relaxation variables forbids any cost
+/// of `c` or more and the CDCL solver, keeping its learnt clauses, is asked
+/// for a cheaper one until none is left.
+#[derive(Debug, Clone, Default)]
+pub struct MaxSatSolver {
+    cnf: Cnf,
+    soft: Vec<(Clause, u64)>,
+}
+
+impl MaxSatSolver {
+    pub fn new (hard: Cnf) -> MaxSatSolver {
+        MaxSatSolver {
+            cnf: hard,
+            soft: Vec::new(),
+        }
+    }
+
+    pub fn from_wcnf (wcnf: &Wcnf) -> MaxSatSolver {
+        let mut solver = MaxSatSolver::new(wcnf.hard.clone());
+
+        for (clause, weight) in wcnf.soft.iter() {
+            solver.add_soft(clause.clone(), *weight);
+        }
+
+        solver
+    }
+
+    /// The hard clauses, whose variables the soft clauses share.
+    pub fn cnf (&self) -> &Cnf {
+        &self.cnf
+    }
+
+    pub fn add_soft (&mut self, clause: Clause, weight: u64) {
+        self.soft.push((clause, weight));
+    }
+
+    /// An optimal assignment indexed by variable with its cost, or `None`
+    /// when the hard clauses are unsatisfiable. Fails when the weights are
+    /// too many and too varied to encode the cost of the first model found.
+    pub fn solve (&self) -> Result<Option<(Vec<bool>, u64)>, MaxSatError> {
+        let mut cnf = self.cnf.clone();
+
+        let relaxed: Vec<(Literal, u64)> = self.soft.iter()
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(clause, weight)| {
+                let relaxation = Literal::positive(cnf.fresh());
+
+                cnf.add_clause(clause.iter().copied().chain([relaxation]).collect());
+
+                (relaxation, *weight)
+            })
+            .collect();
+
+        let mut solver = CdclSolver::from_cnf(&cnf);
+
+        let Some (values) = solver.solve() else {
+            return Ok(None);
+        };
+        let mut best = (self.cost(&values), values);
+
+        if best.0 > 0 {
+            let encoded = cnf.clauses().len();
+            let outputs = cnf.add_weight_counter(&relaxed, best.0)
+                .ok_or(MaxSatError::TooManyClauses(WEIGHT_COUNTER_LIMIT))?;
+
+            for clause in cnf.clauses()[encoded..].iter() {
+                solver.add_clause(clause);
+            }
+
+            while best.0 > 0 {
+                for (_, &output) in outputs.range(best.0..) {
+                    solver.add_clause(&[!output]);
+                }
+
+                match solver.solve() {
+                    Some (values) => best = (self.cost(&values), values),
+                    None => break,
+                }
+            }
+        }
+
+        let (cost, mut values) = best;
+
+        values.truncate(self.cnf.variables());
+
+        Ok(Some((values, cost)))
+    }
+
+    /// Total weight of the soft clauses falsified by `values`, saturating
+    /// at `u64::MAX` as the weight counter does.
+    fn cost (&self, values: &[bool]) -> u64 {
+        self.soft.iter()
+            .filter(|(clause, _)| {
+                !clause.iter().any(|literal| values[literal.variable()] == literal.is_positive())
+            })
+            .fold(0, |cost, (_, weight)| cost.saturating_add(*weight))
+    }
+}
+
+/// A cheapest assignment of a [`MaxSatisfability`], with the total weight of
+/// the soft expressions it falsifies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimum {
+    pub model: Model,
+    pub cost: u64,
+}
+
+/// Finds an assignment making every hard expression true that minimises the
+/// total weight of the soft expressions it makes false.
+pub struct MaxSatisfability<'a> {
+    hard: &'a [Expression],
+    soft: &'a [(Expression, u64)],
+}
+
+impl<'a> MaxSatisfability<'a> {
+    pub fn new (hard: &'a [Expression], soft: &'a [(Expression, u64)]) -> MaxSatisfability<'a> {
+        MaxSatisfability {
+            hard,
+            soft,
+        }
+    }
+
+    /// The optimum, or `None` when the hard expressions cannot hold together.
+    pub fn solve (&self) -> Result<Option<Optimum>, MaxSatError> {
+        let mut cnf = Cnf::new();
+
+        for expression in self.hard.iter() {
+            let literal = cnf.encode(expression);
+
+            cnf.add_clause(vec![literal]);
+        }
+
+        let soft: Vec<(Literal, u64)> = self.soft.iter()
+            .map(|(expression, weight)| (cnf.encode(expression), *weight))
+            .collect();
+
+        let mut solver = MaxSatSolver::new(cnf);
+
+        for (literal, weight) in soft {
+            solver.add_soft(vec![literal], weight);
+        }
+
+        let optimum = solver.solve()?.map(|(values, cost)| Optimum {
+            model: solver.cnf().model(&values),
+            cost,
+        });
+
+        Ok(optimum)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cnf::dimacs;
+    use crate::parse;
+    use crate::testing::{Random, evaluate};
+
+    fn soft (pairs: &[(&str, u64)]) -> Vec<(Expression, u64)> {
+        pairs.iter().map(|(string, weight)| (parse(string).unwrap(), *weight)).collect()
+    }
+
+    /// The cheapest cost over every assignment of `names`.
+    fn brute_force (names: &[&str], hard: &[Expression], soft: &[(Expression, u64)]) -> Option<u64> {
+        (0..1u32 << names.len())
+            .map(|bits| -> Model {
+                names.iter()
+                    .enumerate()
+                    .map(|(index, name)| (name.to_string(), bits & (1 << index) != 0))
+                    .collect()
+            })
+            .filter(|model| hard.iter().all(|expression| evaluate(expression, model)))
+            .map(|model| {
+                soft.iter()
+                    .filter(|(expression, _)| !evaluate(expression, &model))
+                    .map(|(_, weight)| weight)
+                    .sum()
+            })
+            .min()
+    }
+
+    #[test]
+    fn should_prefer_the_heavier_preferences () {
+        let hard = vec![parse("¬(a & b)").unwrap(), parse("c -> a").unwrap()];
+        let soft = soft(&[("a", 2), ("b", 3), ("c", 4)]);
+
+        let optimum = MaxSatisfability::new(&hard, &soft).solve().unwrap().unwrap();
+
+        assert_eq!(optimum.cost, 3);
+        assert_eq!(optimum.model.get("a"), Some(&true));
+        assert_eq!(optimum.model.get("b"), Some(&false));
+        assert_eq!(optimum.model.get("c"), Some(&true));
+    }
+
+    #[test]
+    fn should_report_unsatisfiable_hard_constraints () {
+        let hard = vec![parse("a").unwrap(), parse("¬a").unwrap()];
+
+        assert_eq!(MaxSatisfability::new(&hard, &soft(&[("b", 1)])).solve(), Ok(None));
+    }
+
+    #[test]
+    fn satisfiable_preferences_should_cost_nothing () {
+        let optimum = MaxSatisfability::new(&[], &soft(&[("a | b", 5), ("¬a", 1)])).solve().unwrap().unwrap();
+
+        assert_eq!(optimum.cost, 0);
+        assert_eq!(optimum.model.get("b"), Some(&true));
+    }
+
+    #[test]
+    fn should_solve_wcnf_files () {
+        let wcnf = dimacs::parse_wcnf("p wcnf 3 6 100\n100 -1 -2 0\n100 -2 -3 0\n5 1 0\n6 2 0\n5 3 0\n1 -2 0\n").unwrap();
+
+        let (values, cost) = MaxSatSolver::from_wcnf(&wcnf).solve().unwrap().unwrap();
+
+        assert_eq!(cost, 6);
+        assert_eq!(values, vec![true, false, true]);
+    }
+
+    #[test]
+    fn should_not_overflow_near_the_largest_weights () {
+        let wcnf = dimacs::parse_wcnf("p wcnf 2 3 18446744073709551615\n18446744073709551614 1 0\n18446744073709551614 -1 2 0\n18446744073709551614 -2 0\n").unwrap();
+
+        // Any assignment falsifies one clause, and two weigh more than `u64::MAX`.
+        let (_, cost) = MaxSatSolver::from_wcnf(&wcnf).solve().unwrap().unwrap();
+
+        assert_eq!(cost, u64::MAX - 1);
+    }
+
+    #[test]
+    fn should_refuse_weights_too_varied_to_encode () {
+        let mut cnf = Cnf::new();
+
+        let variables: Vec<usize> = (0..40).map(|index| cnf.variable(format!("x{}", index))).collect();
+
+        let mut solver = MaxSatSolver::new(cnf);
+
+        for (bit, &variable) in variables.iter().enumerate() {
+            solver.add_soft(vec![Literal::positive(variable)], 1 << bit);
+            solver.add_soft(vec![Literal::negative(variable)], 1 << bit);
+        }
+
+        assert_eq!(solver.solve(), Err(MaxSatError::TooManyClauses(WEIGHT_COUNTER_LIMIT)));
+    }
+
+    #[test]
+    fn should_agree_with_brute_force () {
+        let names = ["a", "b", "c", "d"];
+        let mut random = Random::new(0xCBBB9D5DC1059ED8);
+
+        for _ in 0..60 {
+            let hard: Vec<Expression> = (0..random.below(3)).map(|_| random.expression(&names, 2)).collect();
+            let soft: Vec<(Expression, u64)> = (0..6)
+                .map(|_| (random.expression(&names, 2), random.below(10) as u64 + 1))
+                .collect();
+
+            let optimum = MaxSatisfability::new(&hard, &soft).solve().unwrap();
+
+            assert_eq!(optimum.as_ref().map(|optimum| optimum.cost), brute_force(&names, &hard, &soft), "{:?} {:?}", hard, soft);
+
+            if let Some (optimum) = optimum {
+                assert!(hard.iter().all(|expression| evaluate(expression, &optimum.model)));
+
+                let cost: u64 = soft.iter()
+                    .filter(|(expression, _)| !evaluate(expression, &optimum.model))
+                    .map(|(_, weight)| weight)
+                    .sum();
+
+                assert_eq!(cost, optimum.cost);
+            }
+        }
+    }
+}
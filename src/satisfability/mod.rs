@@ -1,12 +1,30 @@
 mod general;
 mod dynamic;
+mod cdcl;
+mod dpll;
+mod all;
+mod count;
+mod equivalence;
+mod entailment;
+mod unsatisfiable_core;
+mod correction;
+mod maxsat;
+mod incremental;
 
-pub use general::GeneralSatisfability;
-pub use dynamic::DynamicSatisfability;
+use std::collections::BTreeMap;
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
-pub enum Expectative {
-    True,
-    False,
-    Any,
-}
+pub use general::{GeneralSatisfability, Expectative};
+pub use dynamic::{DynamicSatisfability, Requirement};
+pub use cdcl::{CdclSatisfability, CdclSolver};
+pub use dpll::{DpllSatisfability, DpllSolver};
+pub use all::{AllSatisfability, Models};
+pub use count::ModelCounter;
+pub use equivalence::Equivalence;
+pub use entailment::Entailment;
+pub use unsatisfiable_core::{UnsatisfiableCore, Extraction};
+pub use correction::{CorrectionSets, Correction, Corrections};
+pub use maxsat::{MaxSatError, MaxSatSolver, MaxSatisfability, Optimum};
+pub use incremental::IncrementalSatisfability;
+
+/// An assignment of truth values to variable names.
+pub type Model = BTreeMap<String, bool>;
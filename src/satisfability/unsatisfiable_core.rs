@@ -0,0 +1,172 @@
+use crate::cnf::Cnf;
+use crate::expression::Expression;
+
+use super::CdclSolver;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    /// Drops every constraint in turn, keeping it only when the rest
+    /// becomes satisfiable without it: one solver call per constraint.
+    Deletion,
+    /// Junker's QuickXplain, which splits the constraints in halves and
+    /// needs far fewer solver calls when the core is small.
+    QuickXplain,
+}
+
+/// Explains why a conjunction of named constraints is unsatisfiable with
+/// a minimal unsatisfiable subset of them: dropping any constraint of the
+/// subset makes the rest satisfiable.
+pub struct UnsatisfiableCore<'a> {
+    constraints: &'a [(String, Expression)],
+    extraction: Extraction,
+}
+
+impl<'a> UnsatisfiableCore<'a> {
+    pub fn new (constraints: &'a [(String, Expression)]) -> UnsatisfiableCore<'a> {
+        UnsatisfiableCore {
+            constraints,
+            extraction: Extraction::QuickXplain,
+        }
+    }
+
+    pub fn extraction (mut self, extraction: Extraction) -> UnsatisfiableCore<'a> {
+        self.extraction = extraction;
+        self
+    }
+
+    /// Names of the constraints of a minimal unsatisfiable subset, in the
+    /// order they were given, or `None` when every constraint holds at once.
+    pub fn minimal (&self) -> Option<Vec<&'a str>> {
+        let all: Vec<usize> = (0..self.constraints.len()).collect();
+
+        if self.satisfiable(&all) {
+            return None;
+        }
+
+        let mut core = match self.extraction {
+            Extraction::Deletion => self.deletion(all),
+            Extraction::QuickXplain => self.quick_xplain(&[], false, &all),
+        };
+
+        core.sort();
+
+        Some(core.into_iter().map(|index| self.constraints[index].0.as_str()).collect())
+    }
+
+    fn deletion (&self, mut core: Vec<usize>) -> Vec<usize> {
+        let mut position = 0;
+
+        while position < core.len() {
+            let removed = core.remove(position);
+
+            if self.satisfiable(&core) {
+                core.insert(position, removed);
+                position += 1;
+            }
+        }
+
+        core
+    }
+
+    /// The minimal subset of `candidates` that is unsatisfiable together
+    /// with `background`, knowing `background` alone is satisfiable unless
+    /// `changed` says it grew since it was last checked.
+    fn quick_xplain (&self, background: &[usize], changed: bool, candidates: &[usize]) -> Vec<usize> {
+        if changed && !self.satisfiable(background) {
+            return Vec::new();
+        }
+
+        if candidates.len() == 1 {
+            return candidates.to_vec();
+        }
+
+        let (first, second) = candidates.split_at(candidates.len() / 2);
+
+        let extended: Vec<usize> = background.iter().chain(first).copied().collect();
+        let second = self.quick_xplain(&extended, !first.is_empty(), second);
+
+        let extended: Vec<usize> = background.iter().chain(second.iter()).copied().collect();
+        let first = self.quick_xplain(&extended, !second.is_empty(), first);
+
+        first.into_iter().chain(second).collect()
+    }
+
+    fn satisfiable (&self, indices: &[usize]) -> bool {
+        let mut cnf = Cnf::new();
+
+        for &index in indices {
+            let literal = cnf.encode(&self.constraints[index].1);
+
+            cnf.add_clause(vec![literal]);
+        }
+
+        CdclSolver::from_cnf(&cnf).solve().is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::{Random, constraints};
+
+    #[test]
+    fn should_find_the_conflicting_rules () {
+        let constraints = constraints(&["a -> b", "c | d", "a", "b -> c", "¬c", "e"]);
+
+        for extraction in [Extraction::Deletion, Extraction::QuickXplain] {
+            assert_eq!(
+                UnsatisfiableCore::new(&constraints).extraction(extraction).minimal(),
+                Some(vec!["a -> b", "a", "b -> c", "¬c"]),
+            );
+        }
+    }
+
+    #[test]
+    fn satisfiable_constraints_should_have_no_core () {
+        let constraints = constraints(&["a | b", "¬a"]);
+
+        assert_eq!(UnsatisfiableCore::new(&constraints).minimal(), None);
+        assert_eq!(UnsatisfiableCore::new(&[]).minimal(), None);
+    }
+
+    #[test]
+    fn should_isolate_a_contradiction () {
+        let constraints = constraints(&["a", "b & ¬b", "c"]);
+
+        assert_eq!(UnsatisfiableCore::new(&constraints).minimal(), Some(vec!["b & ¬b"]));
+    }
+
+    #[test]
+    fn cores_should_be_minimal () {
+        let names = ["a", "b", "c"];
+        let mut random = Random::new(0x1F83D9ABFB41BD6B);
+
+        for _ in 0..50 {
+            let constraints: Vec<(String, Expression)> = (0..8)
+                .map(|index| (format!("c{}", index), random.expression(&names, 2)))
+                .collect();
+
+            let core = UnsatisfiableCore::new(&constraints);
+
+            for extraction in [Extraction::Deletion, Extraction::QuickXplain] {
+                let Some (minimal) = UnsatisfiableCore::new(&constraints).extraction(extraction).minimal() else {
+                    continue;
+                };
+
+                let indices: Vec<usize> = minimal.iter()
+                    .map(|name| constraints.iter().position(|(other, _)| other == name).unwrap())
+                    .collect();
+
+                assert!(!core.satisfiable(&indices));
+
+                for position in 0..indices.len() {
+                    let mut smaller = indices.clone();
+
+                    smaller.remove(position);
+
+                    assert!(core.satisfiable(&smaller), "{:?}", minimal);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::cnf::Literal;
+use crate::expression::Expression;
+use crate::parse;
+use crate::satisfability::Model;
+
+/// Deterministic xorshift generator so randomized tests are reproducible.
+pub struct Random (u64);
+
+impl Random {
+    pub fn new (seed: u64) -> Random {
+        Random(seed)
+    }
+
+    pub fn next (&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below (&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    pub fn clauses (&mut self, variables: usize, clauses: usize, width: usize) -> Vec<Vec<Literal>> {
+        (0..clauses)
+            .map(|_| {
+                (0..width)
+                    .map(|_| Literal::new(self.below(variables), self.below(2) == 0))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn expression (&mut self, names: &[&str], depth: usize) -> Expression {
+        if depth == 0 || self.below(4) == 0 {
+            return match self.below(12) {
+                0 => Expression::True,
+                1 => Expression::False,
+                _ => Expression::var(names[self.below(names.len())]),
+            };
+        }
+
+        let left = Box::new(self.expression(names, depth - 1));
+
+        match self.below(6) {
+            0 => Expression::Not(left),
+            1 => Expression::And(left, Box::new(self.expression(names, depth - 1))),
+            2 => Expression::Or(left, Box::new(self.expression(names, depth - 1))),
+            3 => Expression::Xor(left, Box::new(self.expression(names, depth - 1))),
+            4 => Expression::Implies(left, Box::new(self.expression(names, depth - 1))),
+            _ => Expression::Iff(left, Box::new(self.expression(names, depth - 1))),
+        }
+    }
+}
+
+/// Parses each formula into a constraint named after the formula itself.
+pub fn constraints (strings: &[&str]) -> Vec<(String, Expression)> {
+    strings.iter().map(|string| (string.to_string(), parse(string).unwrap())).collect()
+}
+
+/// Evaluates with unassigned variables defaulting to `false`.
+pub fn evaluate (expression: &Expression, model: &Model) -> bool {
+    let mut model = model.clone();
+
+    for name in expression.variables() {
+        model.entry(name).or_insert(false);
+    }
+
+    expression.evaluate(&model).unwrap()
+}
+
+pub fn satisfied (clauses: &[Vec<Literal>], values: &[bool]) -> bool {
+    clauses.iter().all(|clause| {
+        clause.iter().any(|literal| values[literal.variable()] == literal.is_positive())
+    })
+}
+
+pub fn brute_force (clauses: &[Vec<Literal>], variables: usize) -> bool {
+    (0..1u32 << variables).any(|bits| {
+        let values: Vec<bool> = (0..variables).map(|variable| bits & (1 << variable) != 0).collect();
+
+        satisfied(clauses, &values)
+    })
+}
@@ -0,0 +1,317 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::expression::Expression;
+use crate::satisfability::Model;
+
+/// Largest number of variables [`TruthTable::new`] accepts: 2^16 rows.
+pub const DEFAULT_LIMIT: usize = 16;
+
+/// Largest limit [`TruthTable::with_limit`] honours, so that rows can be
+/// numbered in a `u64`. Anything near it still takes forever to print.
+pub const MAX_LIMIT: usize = 63;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TruthTableError {
+    /// The expression has the first number of variables, above the second.
+    TooManyVariables (usize, usize),
+}
+
+impl fmt::Display for TruthTableError {
+    fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TruthTableError::TooManyVariables (count, limit) => write!(formatter, "the formula has {} variables, more than the limit of {}", count, limit),
+        }
+    }
+}
+
+impl std::error::Error for TruthTableError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Markdown,
+    Csv,
+    Latex,
+}
+
+/// Every assignment of the variables of an expression with its value.
+///
+/// Variables are sorted by name and rows count upwards in binary, the
+/// first variable being the most significant bit. Rows are evaluated as
+/// they are read, never stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruthTable<'a> {
+    expression: &'a Expression,
+    variables: Vec<String>,
+}
+
+impl<'a> TruthTable<'a> {
+    pub fn new (expression: &'a Expression) -> Result<TruthTable<'a>, TruthTableError> {
+        TruthTable::with_limit(expression, DEFAULT_LIMIT)
+    }
+
+    /// Refuses expressions with more than `limit` variables, or more than
+    /// [`MAX_LIMIT`] whatever the limit.
+    pub fn with_limit (expression: &'a Expression, limit: usize) -> Result<TruthTable<'a>, TruthTableError> {
+        let limit = limit.min(MAX_LIMIT);
+        let variables: Vec<String> = expression.variables().into_iter().collect();
+
+        if variables.len() > limit {
+            return Err(TruthTableError::TooManyVariables(variables.len(), limit));
+        }
+
+        Ok(TruthTable {
+            expression,
+            variables,
+        })
+    }
+
+    pub fn variables (&self) -> &[String] {
+        &self.variables
+    }
+
+    /// The values of the variables on each row, and the value of the expression.
+    pub fn rows (&self) -> Rows<'_> {
+        Rows {
+            table: self,
+            next: 0,
+            end: 1 << self.variables.len(),
+        }
+    }
+
+    pub fn render (&self, format: Format) -> String {
+        let mut output = Vec::new();
+
+        self.write(format, &mut output).expect("writing to a vector should not fail");
+
+        String::from_utf8(output).expect("the table should be valid UTF-8")
+    }
+
+    /// Writes the table row by row, so that large tables need not fit in memory.
+    pub fn write<W: Write> (&self, format: Format, output: &mut W) -> io::Result<()> {
+        match format {
+            Format::Text => self.write_text(output),
+            Format::Markdown => self.write_markdown(output),
+            Format::Csv => self.write_csv(output),
+            Format::Latex => self.write_latex(output),
+        }
+    }
+
+    fn write_text<W: Write> (&self, output: &mut W) -> io::Result<()> {
+        for name in self.variables.iter() {
+            write!(output, "{} ", name)?;
+        }
+
+        writeln!(output, "| f")?;
+
+        for (values, value) in self.rows() {
+            for (name, &value) in self.variables.iter().zip(values.iter()) {
+                write!(output, "{:<width$} ", bit(value), width = name.chars().count())?;
+            }
+
+            writeln!(output, "| {}", bit(value))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_markdown<W: Write> (&self, output: &mut W) -> io::Result<()> {
+        let header: Vec<&str> = self.variables.iter().map(String::as_str).chain(["f"]).collect();
+
+        writeln!(output, "| {} |", header.join(" | "))?;
+        writeln!(output, "|{}", "---|".repeat(header.len()))?;
+
+        for (values, value) in self.rows() {
+            let cells: Vec<&str> = values.iter().chain([&value]).map(|&value| bit(value)).collect();
+
+            writeln!(output, "| {} |", cells.join(" | "))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_csv<W: Write> (&self, output: &mut W) -> io::Result<()> {
+        let header: Vec<&str> = self.variables.iter().map(String::as_str).chain(["f"]).collect();
+
+        writeln!(output, "{}", header.join(","))?;
+
+        for (values, value) in self.rows() {
+            let cells: Vec<&str> = values.iter().chain([&value]).map(|&value| bit(value)).collect();
+
+            writeln!(output, "{}", cells.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_latex<W: Write> (&self, output: &mut W) -> io::Result<()> {
+        let header: Vec<String> = self.variables.iter()
+            .map(|name| format!("${}$", name))
+            .chain(["$f$".to_string()])
+            .collect();
+
+        writeln!(output, "\\begin{{tabular}}{{{}|c}}", "c".repeat(self.variables.len()))?;
+        writeln!(output, "{} \\\\\n\\hline", header.join(" & "))?;
+
+        for (values, value) in self.rows() {
+            let cells: Vec<&str> = values.iter().chain([&value]).map(|&value| bit(value)).collect();
+
+            writeln!(output, "{} \\\\", cells.join(" & "))?;
+        }
+
+        writeln!(output, "\\end{{tabular}}")
+    }
+}
+
+/// The rows of a [`TruthTable`], evaluated one at a time.
+pub struct Rows<'a> {
+    table: &'a TruthTable<'a>,
+    next: u64,
+    end: u64,
+}
+
+impl<'a> Iterator for Rows<'a> {
+    type Item = (Vec<bool>, bool);
+
+    fn next (&mut self) -> Option<(Vec<bool>, bool)> {
+        if self.next == self.end {
+            return None;
+        }
+
+        let bits = self.next;
+        let count = self.table.variables.len();
+
+        self.next += 1;
+
+        let values: Vec<bool> = (0..count)
+            .map(|index| bits & (1 << (count - 1 - index)) != 0)
+            .collect();
+
+        let assignment: Model = self.table.variables.iter()
+            .cloned()
+            .zip(values.iter().copied())
+            .collect();
+
+        let value = self.table.expression.evaluate(&assignment)
+            .expect("every variable of the expression is assigned");
+
+        Some((values, value))
+    }
+
+    fn nth (&mut self, skipped: usize) -> Option<(Vec<bool>, bool)> {
+        self.next = self.next.saturating_add(skipped as u64).min(self.end);
+        self.next()
+    }
+
+    fn size_hint (&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.end - self.next).ok();
+
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+fn bit (value: bool) -> &'static str {
+    if value { "1" } else { "0" }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn should_enumerate_rows_in_binary_order () {
+        let expression = parse("b -> a").unwrap();
+        let table = TruthTable::new(&expression).unwrap();
+
+        assert_eq!(table.variables(), &["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            table.rows().collect::<Vec<_>>(),
+            vec![
+                (vec![false, false], true),
+                (vec![false, true], false),
+                (vec![true, false], true),
+                (vec![true, true], true),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_render_every_format () {
+        let expression = parse("long ^ b").unwrap();
+        let table = TruthTable::new(&expression).unwrap();
+
+        assert_eq!(
+            table.render(Format::Text),
+            "b long | f\n0 0    | 0\n0 1    | 1\n1 0    | 1\n1 1    | 0\n",
+        );
+
+        assert_eq!(
+            table.render(Format::Markdown),
+            "| b | long | f |\n|---|---|---|\n| 0 | 0 | 0 |\n| 0 | 1 | 1 |\n| 1 | 0 | 1 |\n| 1 | 1 | 0 |\n",
+        );
+
+        assert_eq!(
+            table.render(Format::Csv),
+            "b,long,f\n0,0,0\n0,1,1\n1,0,1\n1,1,0\n",
+        );
+
+        assert_eq!(
+            table.render(Format::Latex),
+            concat!(
+                "\\begin{tabular}{cc|c}\n",
+                "$b$ & $long$ & $f$ \\\\\n",
+                "\\hline\n",
+                "0 & 0 & 0 \\\\\n",
+                "0 & 1 & 1 \\\\\n",
+                "1 & 0 & 1 \\\\\n",
+                "1 & 1 & 0 \\\\\n",
+                "\\end{tabular}\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn constant_should_have_a_single_row () {
+        let table = TruthTable::new(&Expression::True).unwrap();
+
+        assert_eq!(table.rows().collect::<Vec<_>>(), vec![(vec![], true)]);
+        assert_eq!(table.render(Format::Csv), "f\n1\n");
+    }
+
+    #[test]
+    fn should_refuse_too_many_variables () {
+        let expression = parse("a & b & c").unwrap();
+
+        assert_eq!(
+            TruthTable::with_limit(&expression, 2),
+            Err(TruthTableError::TooManyVariables(3, 2)),
+        );
+    }
+
+    #[test]
+    fn should_cap_the_limit () {
+        let names: Vec<String> = (0..70).map(|index| format!("x{}", index)).collect();
+        let expression = parse(&names.join(" | ")).unwrap();
+
+        assert_eq!(
+            TruthTable::with_limit(&expression, usize::MAX),
+            Err(TruthTableError::TooManyVariables(70, MAX_LIMIT)),
+        );
+    }
+
+    #[test]
+    fn rows_should_be_evaluated_lazily () {
+        let names: Vec<String> = (0..40).map(|index| format!("x{:02}", index)).collect();
+        let expression = parse(&names.join(" & ")).unwrap();
+        let table = TruthTable::with_limit(&expression, 40).unwrap();
+
+        let mut rows = table.rows();
+
+        assert_eq!(rows.size_hint(), (1 << 40, Some(1 << 40)));
+        assert_eq!(rows.next(), Some((vec![false; 40], false)));
+        assert_eq!(rows.nth((1 << 40) - 2), Some((vec![true; 40], true)));
+        assert_eq!(rows.next(), None);
+    }
+}